@@ -1,12 +1,43 @@
 extern crate revisa_minidump;
 
+use revisa_minidump::{Minidump, ParseError};
+use std::error::Error;
 use std::fs::File;
 use std::io::prelude::*;
 
+// Print a parse error along with the chain of contexts that led to it
+fn report_error(what: &str, err: &ParseError) {
+    eprintln!("{}: {}", what, err);
+
+    let mut source = err.source();
+    while let Some(cause) = source {
+        eprintln!("    caused by: {}", cause);
+        source = cause.source();
+    }
+}
+
+// Optional streams which are absent from the dump are not errors
+fn check_json(what: &str, result: Result<Vec<u8>, ParseError>) -> bool {
+    match result {
+        Ok(x) => {
+            let _json = String::from_utf8(x).expect("bad UTF-8");
+            true
+        }
+        Err(ParseError::MissingStream(stream_type)) => {
+            println!("{}: absent ({:?})", what, stream_type);
+            true
+        }
+        Err(e) => {
+            report_error(what, &e);
+            false
+        }
+    }
+}
+
 fn main() {
     let mut buf: Vec<u8> = Vec::new();
 
-    if let Some(fname) = std::env::args().skip(1).next() {
+    if let Some(fname) = std::env::args().nth(1) {
         let mut f = File::open(fname).expect("file not found");
         f.read_to_end(&mut buf).expect("failed to read");
     } else {
@@ -14,30 +45,47 @@ fn main() {
         std::process::exit(1);
     }
 
-    {
-        let x = revisa_minidump::memory_overlay_json(&buf);
-        let _json = String::from_utf8(x).expect("bad UTF-8");
+    // Show annotations left by the dump writer
+    if let Ok(dump) = Minidump::parse(&buf) {
+        if let Ok(comment) = dump.comment_a() {
+            println!("Comment: {}", comment);
+        }
+        if let Ok(comment) = dump.comment_w() {
+            println!("Comment: {}", comment);
+        }
     }
 
-    {
-        let x = revisa_minidump::memory_analysis_json(&buf);
-        let _json = String::from_utf8(x).expect("bad UTF-8");
-    }
-
-    {
-        let x = revisa_minidump::thread_list_json(&buf);
-        let _json = String::from_utf8(x).expect("bad UTF-8");
-    }
+    let results = [
+        check_json("Summary", revisa_minidump::summary_json(&buf)),
+        check_json("Memory overlay", revisa_minidump::memory_overlay_json(&buf)),
+        check_json("Memory analysis", revisa_minidump::memory_analysis_json(&buf)),
+        check_json("Thread list", revisa_minidump::thread_list_json(&buf)),
+        check_json("Module list", revisa_minidump::module_list_json(&buf)),
+        check_json(
+            "Unloaded module list",
+            revisa_minidump::unloaded_module_list_json(&buf),
+        ),
+        check_json("Crash info", revisa_minidump::crash_info_json(&buf)),
+        check_json("Linux info", revisa_minidump::linux_info_json(&buf)),
+        check_json(
+            "Function table list",
+            revisa_minidump::function_table_list_json(&buf),
+        ),
+        check_json("Handle list", revisa_minidump::handle_list_json(&buf)),
+        check_json(
+            "Handle operation list",
+            revisa_minidump::handle_operation_list_json(&buf),
+        ),
+        check_json("Token list", revisa_minidump::token_list_json(&buf)),
+        check_json("Exception record", revisa_minidump::exception_record_json(&buf)),
+        check_json("System info", revisa_minidump::system_info_json(&buf)),
+        check_json("Misc info", revisa_minidump::misc_info_json(&buf)),
+    ];
 
-    {
-        let x = revisa_minidump::exception_record_json(&buf);
-        let _json = String::from_utf8(x).expect("bad UTF-8");
-    }
-
-    {
-        let x = revisa_minidump::system_info_json(&buf);
-        let _json = String::from_utf8(x).expect("bad UTF-8");
+    if results.iter().all(|&ok| ok) {
+        println!("Parse Complete");
+    } else {
+        println!("Parse Complete (with errors)");
+        std::process::exit(2);
     }
-
-    println!("Parse Complete");
 }
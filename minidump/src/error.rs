@@ -0,0 +1,160 @@
+use serde_json;
+use std::error::Error;
+use std::fmt;
+use types::StreamType;
+
+#[derive(Debug)]
+pub enum ParseError {
+    // Not enough bytes remain to decode a structure
+    IncompleteData {
+        expected: u64,
+        actual: u64,
+    },
+    // An RVA or offset points outside of the file
+    InvalidOffset {
+        offset: u64,
+        file_size: u64,
+    },
+    BadMagic,
+    UnsupportedVersion(u16),
+    UnexpectedStreamSize {
+        expected: u64,
+        actual: u64,
+    },
+    UnexpectedEntryCount(u64),
+    InvalidString {
+        rva: u64,
+    },
+    InvalidData(&'static str),
+    // Thread context is for a different CPU to the dump architecture
+    ContextMismatch {
+        architecture: u16,
+        context_flags: u32,
+    },
+    MissingStream(StreamType),
+    // Process memory which was not captured in the dump
+    MissingMemory {
+        address: u64,
+        length: u64,
+        captured: u64,
+    },
+    Serialize(serde_json::Error),
+
+    // Context wrappers which locate where an inner error occurred
+    Stream {
+        stream_type: StreamType,
+        offset: u64,
+        length: u64,
+        source: Box<ParseError>,
+    },
+    Entry {
+        index: u64,
+        offset: u64,
+        source: Box<ParseError>,
+    },
+}
+
+impl ParseError {
+    // Innermost error, skipping any context wrappers
+    pub fn root_cause(&self) -> &ParseError {
+        match *self {
+            ParseError::Stream { ref source, .. } | ParseError::Entry { ref source, .. } => {
+                source.root_cause()
+            }
+            _ => self,
+        }
+    }
+
+    // Describe the error along with each context it was raised in
+    pub fn full_description(&self) -> String {
+        let mut desc = self.to_string();
+
+        let mut source = self.source();
+        while let Some(cause) = source {
+            desc += ": ";
+            desc += &cause.to_string();
+            source = cause.source();
+        }
+
+        desc
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseError::IncompleteData { expected, actual } => write!(
+                f,
+                "Incomplete data: expected {} bytes, found {}",
+                expected, actual
+            ),
+            ParseError::InvalidOffset { offset, file_size } => write!(
+                f,
+                "Offset {:#x} is outside of file (size {:#x})",
+                offset, file_size
+            ),
+            ParseError::BadMagic => write!(f, "Bad header magic"),
+            ParseError::UnsupportedVersion(v) => write!(f, "Unsupported minidump version {}", v),
+            ParseError::UnexpectedStreamSize { expected, actual } => write!(
+                f,
+                "Unexpected stream size: expected {} bytes, found {}",
+                expected, actual
+            ),
+            ParseError::UnexpectedEntryCount(n) => write!(f, "Unexpected number of entries {}", n),
+            ParseError::InvalidString { rva } => write!(f, "Bad UTF-16 string at {:#x}", rva),
+            ParseError::InvalidData(msg) => write!(f, "{}", msg),
+            ParseError::ContextMismatch {
+                architecture,
+                context_flags,
+            } => write!(
+                f,
+                "Context flags {:#x} do not match processor architecture {:#x}",
+                context_flags, architecture
+            ),
+            ParseError::MissingStream(ref stream_type) => {
+                write!(f, "Unable to find {:?}", stream_type)
+            }
+            ParseError::MissingMemory {
+                address,
+                length,
+                captured,
+            } => write!(
+                f,
+                "Memory at {:#x} (length {:#x}) was not captured ({:#x} bytes of the read were)",
+                address, length, captured
+            ),
+            ParseError::Serialize(ref e) => write!(f, "Failed to serialize: {}", e),
+            ParseError::Stream {
+                ref stream_type,
+                offset,
+                length,
+                ..
+            } => write!(
+                f,
+                "Failed to parse {:?} at {:#x} (length {:#x})",
+                stream_type, offset, length
+            ),
+            ParseError::Entry { index, offset, .. } => {
+                write!(f, "Failed to parse entry {} at {:#x}", index, offset)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ParseError::Stream { ref source, .. } | ParseError::Entry { ref source, .. } => {
+                Some(&**source)
+            }
+            ParseError::Serialize(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(e: serde_json::Error) -> Self {
+        ParseError::Serialize(e)
+    }
+}
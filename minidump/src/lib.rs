@@ -5,108 +5,306 @@ extern crate byteorder;
 extern crate serde;
 extern crate serde_json;
 
+mod error;
 mod mem_analysis;
+mod memory_reader;
+mod minidump;
 mod parse;
-mod types;
+pub mod types;
 
-use types::StreamType;
+pub use error::ParseError;
+pub use mem_analysis::{memory_analysis, AllocationRegion, MemoryAnalysis, ProtectionRegion};
+pub use memory_reader::{MemoryReader, MissingRange};
+pub use minidump::Minidump;
+use std::collections::BTreeMap;
+use types::{AssertionInfo, AuxvEntry, BreakpadInfo, CrashpadInfo, ExceptionStream, HandleOperation,
+            LinuxDsoDebug, LinuxMapping, MiscInfo, ProcessVmCounters, SystemInfo, SystemMemoryInfo,
+            Thread};
 
-// Find available overlay data ranges and return as JSON
-pub fn memory_overlay_json(dump: &[u8]) -> Vec<u8> {
-    let header = parse::parse_header(&dump)
-        .map(|(h, _)| h)
-        .expect("Failed to parse minidump::Header");
-
-    let dir = parse::parse_directory(&dump, &header)
-        .map(|(d, _)| d)
-        .expect("Failed to parse minidump Directory list");
-
-    let memstream = dir.iter()
-        .find(|&el| {
-            el.StreamType == StreamType::MemoryListStream as u32
-                || el.StreamType == StreamType::Memory64ListStream as u32
-        })
-        .expect("Unable to find memory stream");
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct ErrorReport {
+    Error: String,
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct SummaryReport<'a> {
+    Version: u16,
+    NumberOfStreams: u32,
+    TimeDateStamp: u32,
+    Flags: u64,
+    CommentA: Option<&'a str>,
+    CommentW: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct ThreadReport<'a> {
+    #[serde(flatten)]
+    Thread: &'a Thread,
+    InstructionPointer: Option<u64>,
+    IpLocation: Option<String>,
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct HandleOperationReport<'a> {
+    #[serde(flatten)]
+    Operation: &'a HandleOperation,
+    BackTraceLocations: Vec<Option<String>>,
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct HandleHistoryReport<'a> {
+    Handle: u64,
+    Operations: Vec<HandleOperationReport<'a>>,
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct ExceptionReport<'a> {
+    #[serde(flatten)]
+    Exception: &'a ExceptionStream,
+    FaultLocation: Option<String>,
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct MiscInfoReport<'a> {
+    #[serde(flatten)]
+    MiscInfo: &'a MiscInfo,
+    ProcessUptime: Option<u32>,
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct SystemInfoReport<'a> {
+    #[serde(flatten)]
+    SystemInfo: &'a SystemInfo,
+    SystemMemoryInfo: Option<&'a SystemMemoryInfo>,
+    ProcessVmCounters: Option<&'a ProcessVmCounters>,
+}
 
-    let parse_fn = if memstream.StreamType == StreamType::Memory64ListStream as u32 {
-        parse::parse_memory64_list
-    } else {
-        parse::parse_memory_list
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct LinuxReport<'a> {
+    CpuInfo: Option<&'a [BTreeMap<String, String>]>,
+    ProcStatus: Option<&'a BTreeMap<String, String>>,
+    LsbRelease: Option<&'a BTreeMap<String, String>>,
+    CmdLine: Option<&'a [String]>,
+    Environ: Option<&'a [String]>,
+    Auxv: Option<&'a [AuxvEntry]>,
+    Maps: Option<&'a [LinuxMapping]>,
+    DsoDebug: Option<&'a LinuxDsoDebug>,
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct CrashInfoReport<'a> {
+    BreakpadInfo: Option<&'a BreakpadInfo>,
+    AssertionInfo: Option<&'a AssertionInfo>,
+    CrashpadInfo: Option<&'a CrashpadInfo>,
+}
+
+// Describe which module contains an address, if any
+fn describe_address(dump: &Minidump, addr: Option<u64>) -> Option<String> {
+    addr.and_then(|addr| dump.lookup_address(addr))
+        .map(|loc| loc.to_string())
+}
+
+// Describe a failure as JSON so that callers expecting JSON can report it
+pub fn error_json(err: &ParseError) -> Vec<u8> {
+    let report = ErrorReport {
+        Error: err.full_description(),
+    };
+
+    serde_json::to_vec(&report).unwrap_or_else(|_| b"{}".to_vec())
+}
+
+// Summarize the dump header and annotations and return as JSON
+pub fn summary_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
+    let header = dump.header();
+
+    let report = SummaryReport {
+        Version: header.Version,
+        NumberOfStreams: header.NumberOfStreams,
+        TimeDateStamp: header.TimeDateStamp,
+        Flags: header.Flags,
+        CommentA: dump.comment_a().ok(),
+        CommentW: dump.comment_w().ok(),
     };
 
-    let ranges = parse_fn(&dump, &memstream.Location)
-        .map(|(v, _)| v)
-        .unwrap();
+    Ok(serde_json::to_vec(&report)?)
+}
+
+// Find available overlay data ranges and return as JSON
+pub fn memory_overlay_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
 
-    serde_json::to_vec(&ranges).expect("Serializing failed")
+    Ok(serde_json::to_vec(dump.memory_list()?)?)
 }
 
 // Analyze memory info into a more useful format
-pub fn memory_analysis_json(dump: &[u8]) -> Vec<u8> {
-    let analysis = mem_analysis::memory_analysis(dump);
+pub fn memory_analysis_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
+    let analysis = mem_analysis::memory_analysis(&dump)?;
 
-    serde_json::to_vec(&analysis).expect("Serializing failed")
+    Ok(serde_json::to_vec(&analysis)?)
 }
 
 // Find list of threads and return as JSON
-pub fn thread_list_json(dump: &[u8]) -> Vec<u8> {
-    let header = parse::parse_header(&dump)
-        .map(|(h, _)| h)
-        .expect("Failed to parse minidump::Header");
+pub fn thread_list_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
+
+    let threads: Vec<_> = dump
+        .threads()?
+        .iter()
+        .map(|thread| {
+            let ip = thread.Context.instruction_pointer();
+
+            ThreadReport {
+                Thread: thread,
+                InstructionPointer: ip,
+                IpLocation: describe_address(&dump, ip),
+            }
+        })
+        .collect();
 
-    let dir = parse::parse_directory(&dump, &header)
-        .map(|(d, _)| d)
-        .expect("Failed to parse minidump Directory list");
+    Ok(serde_json::to_vec(&threads)?)
+}
 
-    let threadlist = dir.iter()
-        .find(|&el| el.StreamType == StreamType::ThreadListStream as u32)
-        .expect("Unable to find thread list");
+// Find list of modules and return as JSON
+pub fn module_list_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
 
-    let threads = parse::parse_thread_list(dump, &threadlist.Location)
-        .map(|(v, _)| v)
-        .unwrap();
+    Ok(serde_json::to_vec(dump.modules()?)?)
+}
 
-    serde_json::to_vec(&threads).expect("Serializing failed")
+// Find list of unloaded modules and return as JSON
+pub fn unloaded_module_list_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
+
+    Ok(serde_json::to_vec(dump.unloaded_modules()?)?)
 }
 
-// Find exception record and return as JSON
-pub fn exception_record_json(dump: &[u8]) -> Vec<u8> {
-    let header = parse::parse_header(&dump)
-        .map(|(h, _)| h)
-        .expect("Failed to parse minidump::Header");
+// Find Linux process information written by Breakpad and return as JSON.
+// Streams which are absent are reported as null.
+pub fn linux_info_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
 
-    let dir = parse::parse_directory(&dump, &header)
-        .map(|(d, _)| d)
-        .expect("Failed to parse minidump Directory list");
+    let report = LinuxReport {
+        CpuInfo: dump.linux_cpu_info().ok(),
+        ProcStatus: dump.linux_proc_status().ok(),
+        LsbRelease: dump.linux_lsb_release().ok(),
+        CmdLine: dump.linux_cmd_line().ok(),
+        Environ: dump.linux_environ().ok(),
+        Auxv: dump.linux_auxv().ok(),
+        Maps: dump.linux_maps().ok(),
+        DsoDebug: dump.linux_dso_debug().ok(),
+    };
+
+    Ok(serde_json::to_vec(&report)?)
+}
 
-    let stream = dir.iter()
-        .find(|&el| el.StreamType == StreamType::ExceptionStream as u32)
-        .expect("Unable to find exception record");
+// Find crash details written by Breakpad or Crashpad and return as JSON.
+// Streams which are absent are reported as null.
+pub fn crash_info_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
 
-    let record = parse::parse_exception_stream(dump, &stream.Location)
-        .map(|(v, _)| v)
-        .unwrap();
+    let report = CrashInfoReport {
+        BreakpadInfo: dump.breakpad_info().ok(),
+        AssertionInfo: dump.assertion_info().ok(),
+        CrashpadInfo: dump.crashpad_info().ok(),
+    };
 
-    serde_json::to_vec(&record).expect("Serializing failed")
+    Ok(serde_json::to_vec(&report)?)
 }
 
-// Find system info and return as JSON
-pub fn system_info_json(dump: &[u8]) -> Vec<u8> {
-    let header = parse::parse_header(&dump)
-        .map(|(h, _)| h)
-        .expect("Failed to parse minidump::Header");
+// Find dynamic function tables and return as JSON
+pub fn function_table_list_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
 
-    let dir = parse::parse_directory(&dump, &header)
-        .map(|(d, _)| d)
-        .expect("Failed to parse minidump Directory list");
+    Ok(serde_json::to_vec(dump.function_tables()?)?)
+}
+
+// Find handle table and return as JSON
+pub fn handle_list_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
+
+    Ok(serde_json::to_vec(dump.handles()?)?)
+}
 
-    let stream = dir.iter()
-        .find(|&el| el.StreamType == StreamType::SystemInfoStream as u32)
-        .expect("Unable to find system info");
+// Find security tokens and return as JSON
+pub fn token_list_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
 
-    let system_info = parse::parse_system_info(dump, &stream.Location)
-        .map(|(v, _)| v)
-        .unwrap();
+    Ok(serde_json::to_vec(dump.tokens()?)?)
+}
+
+// Find handle operations grouped by handle and return as JSON
+pub fn handle_operation_list_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
+
+    let history: Vec<_> = dump
+        .handle_operations_by_handle()?
+        .into_iter()
+        .map(|(handle, operations)| HandleHistoryReport {
+            Handle: handle,
+            Operations: operations
+                .into_iter()
+                .map(|operation| HandleOperationReport {
+                    Operation: operation,
+                    BackTraceLocations: operation
+                        .BackTrace
+                        .iter()
+                        .map(|&addr| describe_address(&dump, Some(addr)))
+                        .collect(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(serde_json::to_vec(&history)?)
+}
+
+// Find exception record and return as JSON
+pub fn exception_record_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
+    let exception = dump.exception()?;
+
+    let report = ExceptionReport {
+        Exception: exception,
+        FaultLocation: describe_address(&dump, Some(exception.Exception.Address)),
+    };
+
+    Ok(serde_json::to_vec(&report)?)
+}
+
+// Find misc info and return as JSON
+pub fn misc_info_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
+
+    let report = MiscInfoReport {
+        MiscInfo: dump.misc_info()?,
+        ProcessUptime: dump.process_uptime(),
+    };
+
+    Ok(serde_json::to_vec(&report)?)
+}
+
+// Find system info, along with system and process memory usage when they
+// are available, and return as JSON
+pub fn system_info_json(dump: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let dump = Minidump::parse(dump)?;
+
+    let report = SystemInfoReport {
+        SystemInfo: dump.system_info()?,
+        SystemMemoryInfo: dump.system_memory_info().ok(),
+        ProcessVmCounters: dump.process_vm_counters().ok(),
+    };
 
-    serde_json::to_vec(&system_info).expect("Serializing failed")
+    Ok(serde_json::to_vec(&report)?)
 }
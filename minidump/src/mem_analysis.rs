@@ -1,7 +1,8 @@
 #![allow(non_snake_case)]
 
-use parse::{self, ParseData};
-use types::{MemoryInfo, Module, StreamType};
+use error::ParseError;
+use minidump::Minidump;
+use types::{LinuxMapping, MemoryInfo, Module, StreamType};
 
 #[derive(Serialize)]
 pub struct ProtectionRegion {
@@ -22,6 +23,15 @@ pub struct AllocationRegion {
     pub Regions: Vec<ProtectionRegion>,
 }
 
+#[derive(Serialize)]
+pub struct MemoryAnalysis {
+    pub Regions: Vec<AllocationRegion>,
+
+    // Inconsistencies found in the memory info, which are reported rather
+    // than aborting the analysis.
+    pub Diagnostics: Vec<String>,
+}
+
 // Page State
 const MEM_COMMIT: u32 = 0x00001000;
 const MEM_RESERVE: u32 = 0x00002000;
@@ -34,17 +44,42 @@ const MEM_IMAGE: u32 = 0x01000000;
 
 // Page Protection
 const PAGE_NOACCESS: u32 = 0x00000001;
+const PAGE_READONLY: u32 = 0x00000002;
+const PAGE_READWRITE: u32 = 0x00000004;
+const PAGE_WRITECOPY: u32 = 0x00000008;
+const PAGE_EXECUTE: u32 = 0x00000010;
+const PAGE_EXECUTE_READ: u32 = 0x00000020;
+const PAGE_EXECUTE_READWRITE: u32 = 0x00000040;
+const PAGE_EXECUTE_WRITECOPY: u32 = 0x00000080;
+
+// Record a diagnostic message when a consistency check fails
+macro_rules! check {
+    ($diagnostics:expr, $cond:expr, $($arg:tt)+) => {
+        if !$cond {
+            $diagnostics.push(format!($($arg)+));
+        }
+    };
+}
 
 // Group memory regions by allocation region
-fn find_allocation_regions(meminfo: &[MemoryInfo]) -> Vec<AllocationRegion> {
+fn find_allocation_regions(
+    meminfo: &[MemoryInfo],
+    diagnostics: &mut Vec<String>,
+) -> Vec<AllocationRegion> {
     const INITIAL_VA: u64 = 0x0;
     let mut next_va: u64 = INITIAL_VA;
     let mut regions = Vec::new();
 
     for info in meminfo {
         // We expect memory info to be in increasing order and complete.
-        assert!(info.BaseAddress == next_va);
-        next_va = info.BaseAddress + info.RegionSize;
+        check!(
+            diagnostics,
+            info.BaseAddress == next_va,
+            "Region {:#x} does not follow previous region end {:#x}",
+            info.BaseAddress,
+            next_va
+        );
+        next_va = info.BaseAddress.wrapping_add(info.RegionSize);
 
         let current_va = regions
             .last()
@@ -54,10 +89,18 @@ fn find_allocation_regions(meminfo: &[MemoryInfo]) -> Vec<AllocationRegion> {
         if info.AllocationBase == INITIAL_VA {
             // An AllocationBase value of 0 should only be used for unallocated
             // memory. Perform checks that the memory flags match expectations.
-            assert!(info.AllocationProtect == 0);
-            assert!(info.Protect == PAGE_NOACCESS);
-            assert!(info.State == MEM_FREE);
-            assert!(info.Type == 0);
+            check!(
+                diagnostics,
+                info.AllocationProtect == 0
+                    && info.Protect == PAGE_NOACCESS
+                    && info.State == MEM_FREE
+                    && info.Type == 0,
+                "Free region {:#x} has unexpected State {:#x}, Protect {:#x}, Type {:#x}",
+                info.BaseAddress,
+                info.State,
+                info.Protect,
+                info.Type
+            );
         } else {
             // Create new AllocationRegion if this info is not in current.
             if info.AllocationBase != current_va {
@@ -70,13 +113,37 @@ fn find_allocation_regions(meminfo: &[MemoryInfo]) -> Vec<AllocationRegion> {
                 });
             }
 
-            let mut current = regions.last_mut().unwrap();
+            let current = regions.last_mut().unwrap();
 
-            assert!(info.AllocationBase == current.AllocationBase);
-            assert!(info.AllocationProtect == current.AllocationProtect);
-            assert!(info.BaseAddress == current.AllocationBase + current.AllocationSize);
-            assert!(info.State == MEM_COMMIT || info.State == MEM_RESERVE);
-            assert!(info.Type == MEM_IMAGE || info.Type == MEM_MAPPED || info.Type == MEM_PRIVATE);
+            check!(
+                diagnostics,
+                info.AllocationProtect == current.AllocationProtect,
+                "Region {:#x} has AllocationProtect {:#x} but its allocation has {:#x}",
+                info.BaseAddress,
+                info.AllocationProtect,
+                current.AllocationProtect
+            );
+            check!(
+                diagnostics,
+                info.BaseAddress == current.AllocationBase.wrapping_add(current.AllocationSize),
+                "Region {:#x} is not contiguous with allocation {:#x}",
+                info.BaseAddress,
+                current.AllocationBase
+            );
+            check!(
+                diagnostics,
+                info.State == MEM_COMMIT || info.State == MEM_RESERVE,
+                "Region {:#x} has unexpected State {:#x}",
+                info.BaseAddress,
+                info.State
+            );
+            check!(
+                diagnostics,
+                info.Type == MEM_IMAGE || info.Type == MEM_MAPPED || info.Type == MEM_PRIVATE,
+                "Region {:#x} has unexpected Type {:#x}",
+                info.BaseAddress,
+                info.Type
+            );
 
             let protect_region = ProtectionRegion {
                 BaseAddress: info.BaseAddress,
@@ -86,7 +153,10 @@ fn find_allocation_regions(meminfo: &[MemoryInfo]) -> Vec<AllocationRegion> {
                 Type: info.Type,
             };
 
-            current.AllocationSize += info.RegionSize;
+            // Measure the allocation by its furthest extent so that gaps are
+            // accounted for if the regions are not contiguous.
+            let limit = info.BaseAddress.wrapping_add(info.RegionSize);
+            current.AllocationSize = limit.wrapping_sub(current.AllocationBase);
             current.Regions.push(protect_region);
         }
     }
@@ -94,57 +164,294 @@ fn find_allocation_regions(meminfo: &[MemoryInfo]) -> Vec<AllocationRegion> {
     regions
 }
 
-fn annotate_modules(regions: &mut [AllocationRegion], modules: &[Module], data: ParseData) {
+fn annotate_modules(
+    regions: &mut [AllocationRegion],
+    modules: &[Module],
+    diagnostics: &mut Vec<String>,
+) {
     for module in modules {
-        let idx = regions
-            .binary_search_by_key(&module.BaseOfImage, |x| x.AllocationBase)
-            .expect("Module doesn't belong to any region");
+        let idx = match regions.binary_search_by_key(&module.BaseOfImage, |x| x.AllocationBase) {
+            Ok(idx) => idx,
+            Err(_) => {
+                diagnostics.push(format!(
+                    "Module at {:#x} doesn't belong to any region",
+                    module.BaseOfImage
+                ));
+                continue;
+            }
+        };
 
         let region = &mut regions[idx];
 
-        assert!(region.AllocationSize == module.SizeOfImage as u64);
-        region
-            .Regions
-            .iter()
-            .for_each(|x| assert!(x.Type == MEM_IMAGE));
+        check!(
+            diagnostics,
+            region.AllocationSize == module.SizeOfImage as u64,
+            "Module at {:#x} has size {:#x} but its allocation has size {:#x}",
+            module.BaseOfImage,
+            module.SizeOfImage,
+            region.AllocationSize
+        );
+        check!(
+            diagnostics,
+            region.Regions.iter().all(|x| x.Type == MEM_IMAGE),
+            "Module at {:#x} contains regions which are not MEM_IMAGE",
+            module.BaseOfImage
+        );
 
         // Annotate module name
-        region.ModuleName = parse::parse_string(data, module.ModuleNameRva)
-            .map(|(n, _)| n)
-            .ok();
-        assert!(region.ModuleName.is_some());
+        region.ModuleName = module.ModuleName.clone();
+        check!(
+            diagnostics,
+            region.ModuleName.is_some(),
+            "Module at {:#x} has no name",
+            module.BaseOfImage
+        );
+    }
+}
+
+// Translate Linux mapping permissions into the closest Windows protection.
+// Private writable file mappings are copy-on-write.
+fn linux_protection(map: &LinuxMapping) -> u32 {
+    let copy_on_write = linux_file_path(map).is_some() && !map.shared();
+
+    match (map.readable(), map.writable(), map.executable()) {
+        (false, false, false) => PAGE_NOACCESS,
+        (true, false, false) => PAGE_READONLY,
+        (_, true, false) if copy_on_write => PAGE_WRITECOPY,
+        (_, true, false) => PAGE_READWRITE,
+        (false, false, true) => PAGE_EXECUTE,
+        (true, false, true) => PAGE_EXECUTE_READ,
+        (_, true, true) if copy_on_write => PAGE_EXECUTE_WRITECOPY,
+        (_, true, true) => PAGE_EXECUTE_READWRITE,
+    }
+}
+
+// Only real files name a module, not pseudo paths such as [stack]
+fn linux_file_path(map: &LinuxMapping) -> Option<&str> {
+    map.Path.as_deref().filter(|path| path.starts_with('/'))
+}
+
+// Build allocation regions from the /proc/self/maps of a Linux process.
+// Consecutive mappings of the same file are grouped into one allocation, and
+// files with executable mappings are treated as images.
+fn find_linux_regions(
+    maps: &[LinuxMapping],
+    diagnostics: &mut Vec<String>,
+) -> Vec<AllocationRegion> {
+    let mut regions: Vec<AllocationRegion> = Vec::new();
+    let mut next_va = 0;
+
+    for (i, map) in maps.iter().enumerate() {
+        // We expect mappings to be in increasing order and not overlap.
+        check!(
+            diagnostics,
+            map.Start >= next_va && map.End > map.Start,
+            "Mapping {:#x}-{:#x} is out of order or empty",
+            map.Start,
+            map.End
+        );
+        next_va = map.End;
+
+        let path = linux_file_path(map);
+        let same_file = |prev: &LinuxMapping| {
+            path.is_some()
+                && linux_file_path(prev) == path
+                && prev.Inode == map.Inode
+                && prev.End == map.Start
+        };
+
+        if i == 0 || path.is_none() || !same_file(&maps[i - 1]) {
+            regions.push(AllocationRegion {
+                AllocationBase: map.Start,
+                AllocationSize: 0,
+                AllocationProtect: linux_protection(map),
+                ModuleName: path.map(str::to_string),
+                Regions: Vec::new(),
+            });
+        }
+
+        let Type = match path {
+            Some(_) => MEM_MAPPED,
+            None if map.shared() => MEM_MAPPED,
+            None => MEM_PRIVATE,
+        };
+
+        // Inaccessible anonymous mappings are address space reservations
+        let Protect = linux_protection(map);
+        let State = if Protect == PAGE_NOACCESS && path.is_none() {
+            MEM_RESERVE
+        } else {
+            MEM_COMMIT
+        };
+
+        let current = regions.last_mut().unwrap();
+        current.Regions.push(ProtectionRegion {
+            BaseAddress: map.Start,
+            RegionSize: map.End.wrapping_sub(map.Start),
+            State,
+            Protect,
+            Type,
+        });
+        current.AllocationSize = map.End.wrapping_sub(current.AllocationBase);
+    }
+
+    // Mark mapped files containing code as images
+    let execute =
+        PAGE_EXECUTE | PAGE_EXECUTE_READ | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY;
+    for region in &mut regions {
+        let executable = region.Regions.iter().any(|x| x.Protect & execute != 0);
+        if region.ModuleName.is_some() && executable {
+            for x in &mut region.Regions {
+                x.Type = MEM_IMAGE;
+            }
+        }
     }
+
+    regions
 }
 
 // Rebuilds minidump data into a more useful format
-pub fn memory_analysis(data: ParseData) -> Vec<AllocationRegion> {
-    let header = parse::parse_header(data)
-        .map(|(h, _)| h)
-        .expect("Failed to parse minidump::Header");
+pub fn memory_analysis(dump: &Minidump) -> Result<MemoryAnalysis, ParseError> {
+    let mut diagnostics = Vec::new();
 
-    let dir = parse::parse_directory(data, &header)
-        .map(|(d, _)| d)
-        .expect("Failed to parse minidump Directory list");
+    // Linux dumps have no memory info, but describe the address space with a
+    // copy of /proc/self/maps which names mapped files directly.
+    if !dump.has_stream(StreamType::MemoryInfoListStream)
+        && dump.has_stream(StreamType::LinuxMapsStream)
+    {
+        let alloc_regions = find_linux_regions(dump.linux_maps()?, &mut diagnostics);
 
-    let meminfostream = dir.iter()
-        .find(|&el| el.StreamType == StreamType::MemoryInfoListStream as u32)
-        .expect("Unable to find memory info stream");
+        return Ok(MemoryAnalysis {
+            Regions: alloc_regions,
+            Diagnostics: diagnostics,
+        });
+    }
 
-    let meminfo = parse::parse_memory_info(data, &meminfostream.Location)
-        .map(|(v, _)| v)
-        .unwrap();
+    let meminfo = dump.memory_info()?;
+    let mut alloc_regions = find_allocation_regions(meminfo, &mut diagnostics);
 
-    let mut alloc_regions = find_allocation_regions(&meminfo);
+    // The memory map is still useful without module names, so problems with
+    // the module list are only reported.
+    match dump.modules() {
+        Ok(modules) => annotate_modules(&mut alloc_regions, modules, &mut diagnostics),
+        Err(e) => diagnostics.push(e.to_string()),
+    }
 
-    let modulestream = dir.iter()
-        .find(|&el| el.StreamType == StreamType::ModuleListStream as u32)
-        .expect("Unable to find module list stream");
+    Ok(MemoryAnalysis {
+        Regions: alloc_regions,
+        Diagnostics: diagnostics,
+    })
+}
 
-    let modules = parse::parse_module_list(data, &modulestream.Location)
-        .map(|(v, _)| v)
-        .unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parse::parse_linux_maps;
+    use types::LocationDescriptor;
 
-    annotate_modules(&mut alloc_regions, &modules, data);
+    fn linux_maps(text: &str) -> Vec<LinuxMapping> {
+        let loc = LocationDescriptor {
+            Offset: 0,
+            Length: text.len() as u64,
+        };
+        parse_linux_maps(text.as_bytes(), &loc).unwrap().0
+    }
+
+    // (BaseAddress, State, Protect, Type)
+    type Part = (u64, u32, u32, u32);
+
+    // (AllocationBase, ModuleName, parts)
+    fn summary(regions: &[AllocationRegion]) -> Vec<(u64, Option<&str>, Vec<Part>)> {
+        regions
+            .iter()
+            .map(|region| {
+                let parts = region
+                    .Regions
+                    .iter()
+                    .map(|x| (x.BaseAddress, x.State, x.Protect, x.Type))
+                    .collect();
+                (region.AllocationBase, region.ModuleName.as_deref(), parts)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn linux_regions() {
+        let maps = linux_maps(concat!(
+            "00400000-00452000 r-xp 00000000 08:02 173521 /usr/bin/app\n",
+            "00452000-00453000 r--p 00052000 08:02 173521 /usr/bin/app\n",
+            "00453000-00456000 rw-p 00053000 08:02 173521 /usr/bin/app\n",
+            "01a3e000-01a5f000 rw-p 00000000 00:00 0 [heap]\n",
+            "7f0000000000-7f0000001000 ---p 00000000 00:00 0\n",
+            "7f0000001000-7f0000021000 rw-p 00000000 00:00 0\n",
+            "7f1000000000-7f1000010000 r--p 00000000 08:02 42 /usr/share/locale/data\n",
+            "7f1000010000-7f1000011000 rw-s 00000000 00:05 7 /dev/shm/buf\n",
+            "7ffc00000000-7ffc00021000 rw-p 00000000 00:00 0 [stack]\n",
+        ));
 
-    alloc_regions
+        let mut diagnostics = Vec::new();
+        let regions = find_linux_regions(&maps, &mut diagnostics);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+
+        let image = Some("/usr/bin/app");
+        let locale = Some("/usr/share/locale/data");
+        let shm = Some("/dev/shm/buf");
+        assert_eq!(
+            summary(&regions),
+            [
+                (
+                    0x400000,
+                    image,
+                    vec![
+                        (0x400000, MEM_COMMIT, PAGE_EXECUTE_READ, MEM_IMAGE),
+                        (0x452000, MEM_COMMIT, PAGE_READONLY, MEM_IMAGE),
+                        (0x453000, MEM_COMMIT, PAGE_WRITECOPY, MEM_IMAGE),
+                    ]
+                ),
+                (0x1a3e000, None, vec![(0x1a3e000, MEM_COMMIT, PAGE_READWRITE, MEM_PRIVATE)]),
+                (
+                    0x7f0000000000,
+                    None,
+                    vec![(0x7f0000000000, MEM_RESERVE, PAGE_NOACCESS, MEM_PRIVATE)]
+                ),
+                (
+                    0x7f0000001000,
+                    None,
+                    vec![(0x7f0000001000, MEM_COMMIT, PAGE_READWRITE, MEM_PRIVATE)]
+                ),
+                (
+                    0x7f1000000000,
+                    locale,
+                    vec![(0x7f1000000000, MEM_COMMIT, PAGE_READONLY, MEM_MAPPED)]
+                ),
+                (
+                    0x7f1000010000,
+                    shm,
+                    vec![(0x7f1000010000, MEM_COMMIT, PAGE_READWRITE, MEM_MAPPED)]
+                ),
+                (
+                    0x7ffc00000000,
+                    None,
+                    vec![(0x7ffc00000000, MEM_COMMIT, PAGE_READWRITE, MEM_PRIVATE)]
+                ),
+            ]
+        );
+        assert_eq!(regions[0].AllocationSize, 0x56000);
+    }
+
+    #[test]
+    fn linux_protection_mapping() {
+        let maps = linux_maps(concat!(
+            "1000-2000 --xp 00000000 00:00 0\n",
+            "2000-3000 rwxp 00000000 00:00 0\n",
+            "3000-4000 rwxp 00000000 08:02 5 /usr/lib/libjit.so\n",
+            "4000-5000 rwxs 00000000 08:02 6 /dev/shm/code\n",
+        ));
+
+        let protect: Vec<_> = maps.iter().map(linux_protection).collect();
+        assert_eq!(
+            protect,
+            [PAGE_EXECUTE, PAGE_EXECUTE_READWRITE, PAGE_EXECUTE_WRITECOPY, PAGE_EXECUTE_READWRITE]
+        );
+    }
 }
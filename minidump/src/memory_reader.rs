@@ -0,0 +1,302 @@
+#![allow(non_snake_case)]
+
+use byteorder::{ByteOrder, LittleEndian};
+use error::ParseError;
+use std::cmp;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use types::{LocationDescriptor, OverlayDescriptor};
+
+// Addresses which were not captured in the dump
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct MissingRange {
+    pub Address: u64,
+    pub Size: u64,
+}
+
+// Random access to the process memory captured in a dump. Ranges are indexed
+// by start address so that reads need not scan the memory list, and a read may
+// span several adjacent ranges.
+pub struct MemoryReader<'a> {
+    data: &'a [u8],
+    ranges: BTreeMap<u64, LocationDescriptor>,
+    is_64bit: bool,
+}
+
+impl<'a> MemoryReader<'a> {
+    // Index the memory ranges of a dump. Overlapping ranges are trimmed so
+    // that each address belongs to a single indexed range.
+    pub fn new(data: &'a [u8], ranges: &[OverlayDescriptor], is_64bit: bool) -> MemoryReader<'a> {
+        let mut sorted: Vec<_> = ranges.iter().filter(|range| range.Location.Length > 0).collect();
+        sorted.sort_by_key(|range| range.Address);
+
+        let mut index = BTreeMap::new();
+        let mut covered: Option<u64> = None;
+        for range in sorted {
+            // Skip the part already covered by an earlier range
+            let skip = covered.map_or(0, |end| end.saturating_sub(range.Address));
+            if skip >= range.Location.Length {
+                continue;
+            }
+
+            let loc = LocationDescriptor {
+                Offset: range.Location.Offset.saturating_add(skip),
+                Length: range.Location.Length - skip,
+            };
+            index.insert(range.Address + skip, loc);
+            covered = Some(range.Address.saturating_add(range.Location.Length));
+        }
+
+        MemoryReader {
+            data,
+            ranges: index,
+            is_64bit,
+        }
+    }
+
+    // Find the captured range containing an address
+    fn range_at(&self, addr: u64) -> Option<(u64, &LocationDescriptor)> {
+        let (&start, loc) = self.ranges.range(..=addr).next_back()?;
+
+        if addr - start < loc.Length {
+            Some((start, loc))
+        } else {
+            None
+        }
+    }
+
+    // Captured bytes from an address up to the end of its range, or to `end`
+    fn captured_at(&self, pos: u64, end: u64) -> Option<&'a [u8]> {
+        let (start, loc) = self.range_at(pos)?;
+        let offset = pos - start;
+        let count = cmp::min(loc.Length - offset, end - pos);
+
+        let file_offset = usize::try_from(loc.Offset.checked_add(offset)?).ok()?;
+        let file_end = file_offset.checked_add(usize::try_from(count).ok()?)?;
+
+        self.data.get(file_offset..file_end)
+    }
+
+    // Walk the addresses from addr to end in order, passing each captured part
+    // with its bytes and each missing part with None
+    fn walk<F>(&self, addr: u64, end: u64, mut visit: F)
+    where
+        F: FnMut(u64, u64, Option<&'a [u8]>),
+    {
+        let mut pos = addr;
+        while pos < end {
+            if let Some(src) = self.captured_at(pos, end) {
+                visit(pos, src.len() as u64, Some(src));
+                pos += src.len() as u64;
+                continue;
+            }
+
+            // Skip ahead to the next captured range
+            let next = self
+                .ranges
+                .range(pos + 1..)
+                .next()
+                .map_or(end, |(&start, _)| cmp::min(start, end));
+
+            visit(pos, next - pos, None);
+            pos = next;
+        }
+    }
+
+    // Read memory, filling bytes which were not captured with zero. The
+    // missing ranges are returned in address order. A dump cannot hold more
+    // memory than its own size, so the returned bytes are cut short for longer
+    // reads, though missing ranges still cover the whole request.
+    pub fn read_bytes_partial(&self, addr: u64, len: u64) -> (Vec<u8>, Vec<MissingRange>) {
+        let end = addr.saturating_add(len);
+        let mut buf = vec![0; cmp::min(end - addr, self.data.len() as u64) as usize];
+        let mut missing = Vec::new();
+
+        self.walk(addr, end, |pos, size, src| match src {
+            Some(src) => {
+                let dst = (pos - addr) as usize;
+                if dst < buf.len() {
+                    let count = cmp::min(src.len(), buf.len() - dst);
+                    buf[dst..dst + count].copy_from_slice(&src[..count]);
+                }
+            }
+            None => add_missing(&mut missing, pos, size),
+        });
+
+        (buf, missing)
+    }
+
+    // Read memory, failing if any byte was not captured. The error reports the
+    // first gap and how much of the read was captured.
+    pub fn read_bytes(&self, addr: u64, len: u64) -> Result<Vec<u8>, ParseError> {
+        let (buf, missing) = self.read_bytes_partial(addr, len);
+        let captured = len - missing.iter().map(|gap| gap.Size).sum::<u64>();
+
+        if let Some(gap) = missing.first() {
+            return Err(ParseError::MissingMemory {
+                address: gap.Address,
+                length: gap.Size,
+                captured,
+            });
+        }
+
+        // Reads past the end of the address space, or too long to return
+        if (buf.len() as u64) < len {
+            return Err(ParseError::MissingMemory {
+                address: addr + buf.len() as u64,
+                length: len - buf.len() as u64,
+                captured: buf.len() as u64,
+            });
+        }
+
+        Ok(buf)
+    }
+
+    // Addresses in a range which were not captured
+    pub fn missing_ranges(&self, addr: u64, len: u64) -> Vec<MissingRange> {
+        let mut missing = Vec::new();
+
+        self.walk(addr, addr.saturating_add(len), |pos, size, src| {
+            if src.is_none() {
+                add_missing(&mut missing, pos, size);
+            }
+        });
+
+        missing
+    }
+
+    pub fn read_u32(&self, addr: u64) -> Result<u32, ParseError> {
+        Ok(LittleEndian::read_u32(&self.read_bytes(addr, 4)?))
+    }
+
+    pub fn read_u64(&self, addr: u64) -> Result<u64, ParseError> {
+        Ok(LittleEndian::read_u64(&self.read_bytes(addr, 8)?))
+    }
+
+    // Read a pointer sized for the dumped process
+    pub fn read_ptr(&self, addr: u64) -> Result<u64, ParseError> {
+        if self.is_64bit {
+            self.read_u64(addr)
+        } else {
+            self.read_u32(addr).map(u64::from)
+        }
+    }
+}
+
+// Record a missing part, merging it with the previous one if they are adjacent
+fn add_missing(missing: &mut Vec<MissingRange>, Address: u64, Size: u64) {
+    match missing.last_mut() {
+        Some(last) if last.Address + last.Size == Address => last.Size += Size,
+        _ => missing.push(MissingRange { Address, Size }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(Address: u64, Offset: u64, Length: u64) -> OverlayDescriptor {
+        OverlayDescriptor {
+            Address,
+            Location: LocationDescriptor { Offset, Length },
+        }
+    }
+
+    // File bytes are their own offsets, so reads show where they came from
+    fn file() -> Vec<u8> {
+        (0..=255).collect()
+    }
+
+    #[test]
+    fn read_across_adjacent_ranges() {
+        let data = file();
+        let memory = MemoryReader::new(&data, &[range(0x1004, 40, 4), range(0x1000, 16, 4)], true);
+
+        assert_eq!(memory.read_bytes(0x1002, 4).unwrap(), [18, 19, 40, 41]);
+        assert!(memory.missing_ranges(0x1000, 8).is_empty());
+    }
+
+    #[test]
+    fn read_with_gap() {
+        let data = file();
+        let memory = MemoryReader::new(&data, &[range(0x1000, 16, 4), range(0x1008, 40, 4)], true);
+
+        let (buf, missing) = memory.read_bytes_partial(0x1002, 8);
+        assert_eq!(buf, [18, 19, 0, 0, 0, 0, 40, 41]);
+        assert_eq!(missing, [MissingRange { Address: 0x1004, Size: 4 }]);
+
+        match memory.read_bytes(0x1002, 8) {
+            Err(ParseError::MissingMemory {
+                address,
+                length,
+                captured,
+            }) => {
+                assert_eq!((address, length, captured), (0x1004, 4, 4));
+            }
+            _ => panic!("gap was not reported"),
+        }
+    }
+
+    #[test]
+    fn read_past_last_range() {
+        let data = file();
+        let memory = MemoryReader::new(&data, &[range(0x1000, 16, 4)], true);
+
+        let (buf, missing) = memory.read_bytes_partial(0x1002, 4);
+        assert_eq!(buf, [18, 19, 0, 0]);
+        assert_eq!(missing, [MissingRange { Address: 0x1004, Size: 2 }]);
+        match memory.read_bytes(0x1002, 4) {
+            Err(ParseError::MissingMemory {
+                address,
+                length,
+                captured,
+            }) => {
+                assert_eq!((address, length, captured), (0x1004, 2, 2));
+            }
+            _ => panic!("partial read was not reported"),
+        }
+
+        // Long reads are not allocated in full, but gaps are still reported
+        let (buf, missing) = memory.read_bytes_partial(0x1000, u64::MAX);
+        assert_eq!(buf.len(), data.len());
+        assert_eq!(buf[..4], [16, 17, 18, 19]);
+        assert_eq!(missing, memory.missing_ranges(0x1000, u64::MAX));
+        assert_eq!(missing, [MissingRange { Address: 0x1004, Size: u64::MAX - 0x1004 }]);
+        assert!(memory.read_bytes(0x1000, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn read_nested_ranges() {
+        let data = file();
+        let ranges = [range(0x1000, 16, 16), range(0x1004, 100, 4), range(0x100c, 200, 8)];
+        let memory = MemoryReader::new(&data, &ranges, true);
+
+        assert_eq!(memory.read_bytes(0x1008, 4).unwrap(), [24, 25, 26, 27]);
+        assert_eq!(memory.read_bytes(0x100e, 4).unwrap(), [30, 31, 204, 205]);
+    }
+
+    #[test]
+    fn read_invalid_file_offset() {
+        let data = file();
+        let memory = MemoryReader::new(&data, &[range(0x1000, u64::MAX - 1, 4)], true);
+
+        assert_eq!(
+            memory.missing_ranges(0x1000, 4),
+            [MissingRange { Address: 0x1000, Size: 4 }]
+        );
+    }
+
+    #[test]
+    fn read_ptr_width() {
+        let data = file();
+        let ranges = [range(0x1000, 16, 8)];
+
+        let memory = MemoryReader::new(&data, &ranges, false);
+        assert_eq!(memory.read_ptr(0x1000).unwrap(), 0x1312_1110);
+        assert!(memory.read_ptr(0x1006).is_err());
+
+        let memory = MemoryReader::new(&data, &ranges, true);
+        assert_eq!(memory.read_ptr(0x1000).unwrap(), 0x1716_1514_1312_1110);
+        assert!(memory.read_ptr(0x1004).is_err());
+    }
+}
@@ -0,0 +1,591 @@
+use byteorder::{ByteOrder, LittleEndian};
+use error::ParseError;
+use memory_reader::MemoryReader;
+use parse::{self, ContextInfo, ParseData, IMAGE_FILE_MACHINE_I386, PROCESSOR_ARCHITECTURE_AMD64,
+            PROCESSOR_ARCHITECTURE_ARM, PROCESSOR_ARCHITECTURE_INTEL, PROCESSOR_ARCHITECTURE_MIPS,
+            PROCESSOR_ARCHITECTURE_RISCV, PROCESSOR_ARCHITECTURE_SPARC};
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, HashMap};
+use types::{AddressLocation, AssertionInfo, AuxvEntry, BreakpadInfo, ContextX86, CrashpadInfo,
+            Directory, ExceptionStream, FunctionTable, HandleDescriptor, HandleOperation, Header,
+            LinuxDsoDebug, LinuxMapping, LocationDescriptor, MemoryInfo, MiscInfo, Module,
+            OverlayDescriptor, ProcessVmCounters, StreamType, SystemInfo, SystemMemoryInfo, Thread,
+            ThreadInfo, ThreadName, TokenInfo, UnloadedModule};
+
+// A minidump file whose header and stream directory have been parsed. Streams
+// are parsed on first access and cached.
+pub struct Minidump<'a> {
+    data: ParseData<'a>,
+    header: Header,
+    directory: Vec<Directory>,
+    streams: HashMap<StreamType, LocationDescriptor>,
+
+    threads: OnceCell<Vec<Thread>>,
+    thread_infos: OnceCell<Vec<ThreadInfo>>,
+    thread_names: OnceCell<Vec<ThreadName>>,
+    modules: OnceCell<Vec<Module>>,
+    unloaded_modules: OnceCell<Vec<UnloadedModule>>,
+    memory_list: OnceCell<Vec<OverlayDescriptor>>,
+    memory_reader: OnceCell<MemoryReader<'a>>,
+    memory_info: OnceCell<Vec<MemoryInfo>>,
+    handles: OnceCell<Vec<HandleDescriptor>>,
+    handle_operations: OnceCell<Vec<HandleOperation>>,
+    tokens: OnceCell<Vec<TokenInfo>>,
+    function_tables: OnceCell<Vec<FunctionTable>>,
+    exception: OnceCell<ExceptionStream>,
+    system_info: OnceCell<SystemInfo>,
+    misc_info: OnceCell<MiscInfo>,
+    system_memory_info: OnceCell<SystemMemoryInfo>,
+    process_vm_counters: OnceCell<ProcessVmCounters>,
+    comment_a: OnceCell<String>,
+    comment_w: OnceCell<String>,
+    linux_cpu_info: OnceCell<Vec<BTreeMap<String, String>>>,
+    linux_proc_status: OnceCell<BTreeMap<String, String>>,
+    linux_lsb_release: OnceCell<BTreeMap<String, String>>,
+    linux_cmd_line: OnceCell<Vec<String>>,
+    linux_environ: OnceCell<Vec<String>>,
+    linux_auxv: OnceCell<Vec<AuxvEntry>>,
+    linux_maps: OnceCell<Vec<LinuxMapping>>,
+    linux_dso_debug: OnceCell<LinuxDsoDebug>,
+    breakpad_info: OnceCell<BreakpadInfo>,
+    assertion_info: OnceCell<AssertionInfo>,
+    crashpad_info: OnceCell<CrashpadInfo>,
+}
+
+// Return cached value, or compute and cache it. Errors are not cached.
+fn cached<T, F>(cell: &OnceCell<T>, f: F) -> Result<&T, ParseError>
+where
+    F: FnOnce() -> Result<T, ParseError>,
+{
+    if let Some(value) = cell.get() {
+        return Ok(value);
+    }
+
+    let value = f()?;
+    Ok(cell.get_or_init(|| value))
+}
+
+impl<'a> Minidump<'a> {
+    pub fn parse(data: ParseData<'a>) -> Result<Minidump<'a>, ParseError> {
+        let (header, _) = parse::parse_header(data)?;
+        let (directory, _) = parse::parse_directory(data, &header)?;
+
+        // Index known streams by type. If a stream type appears more than once,
+        // the first entry is used.
+        let mut streams = HashMap::new();
+        for entry in &directory {
+            if let Some(stream_type) = StreamType::from_u32(entry.StreamType) {
+                streams.entry(stream_type).or_insert(entry.Location);
+            }
+        }
+
+        Ok(Minidump {
+            data,
+            header,
+            directory,
+            streams,
+
+            threads: OnceCell::new(),
+            thread_infos: OnceCell::new(),
+            thread_names: OnceCell::new(),
+            modules: OnceCell::new(),
+            unloaded_modules: OnceCell::new(),
+            memory_list: OnceCell::new(),
+            memory_reader: OnceCell::new(),
+            memory_info: OnceCell::new(),
+            handles: OnceCell::new(),
+            handle_operations: OnceCell::new(),
+            tokens: OnceCell::new(),
+            function_tables: OnceCell::new(),
+            exception: OnceCell::new(),
+            system_info: OnceCell::new(),
+            misc_info: OnceCell::new(),
+            system_memory_info: OnceCell::new(),
+            process_vm_counters: OnceCell::new(),
+            comment_a: OnceCell::new(),
+            comment_w: OnceCell::new(),
+            linux_cpu_info: OnceCell::new(),
+            linux_proc_status: OnceCell::new(),
+            linux_lsb_release: OnceCell::new(),
+            linux_cmd_line: OnceCell::new(),
+            linux_environ: OnceCell::new(),
+            linux_auxv: OnceCell::new(),
+            linux_maps: OnceCell::new(),
+            linux_dso_debug: OnceCell::new(),
+            breakpad_info: OnceCell::new(),
+            assertion_info: OnceCell::new(),
+            crashpad_info: OnceCell::new(),
+        })
+    }
+
+    pub fn data(&self) -> ParseData<'a> {
+        self.data
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    // Raw directory entries, including stream types we do not recognize
+    pub fn directory(&self) -> &[Directory] {
+        &self.directory
+    }
+
+    pub fn has_stream(&self, stream_type: StreamType) -> bool {
+        self.streams.contains_key(&stream_type)
+    }
+
+    pub fn stream_location(
+        &self,
+        stream_type: StreamType,
+    ) -> Result<&LocationDescriptor, ParseError> {
+        self.streams
+            .get(&stream_type)
+            .ok_or(ParseError::MissingStream(stream_type))
+    }
+
+    // Threads come from the ThreadList stream, or the ThreadExList stream if
+    // that is absent. They are annotated with data from supplementary streams
+    // when those streams are present and valid.
+    pub fn threads(&self) -> Result<&[Thread], ParseError> {
+        cached(&self.threads, || {
+            let (mut threads, _) = match self.streams.get(&StreamType::ThreadExListStream) {
+                Some(loc) if !self.has_stream(StreamType::ThreadListStream) => {
+                    parse::parse_thread_ex_list(self.data, loc, self.context_info())?
+                }
+                _ => {
+                    let loc = self.stream_location(StreamType::ThreadListStream)?;
+                    parse::parse_thread_list(self.data, loc, self.context_info())?
+                }
+            };
+
+            if let Ok(infos) = self.thread_infos() {
+                for thread in &mut threads {
+                    thread.Info = infos
+                        .iter()
+                        .find(|info| info.ThreadId == thread.ThreadId)
+                        .cloned();
+                }
+            }
+
+            if let Ok(names) = self.thread_names() {
+                for thread in &mut threads {
+                    thread.Name = names
+                        .iter()
+                        .find(|name| name.ThreadId == thread.ThreadId)
+                        .map(|name| name.Name.clone());
+                }
+            }
+
+            if self.is_wow64() {
+                for thread in &mut threads {
+                    thread.Wow64Context = self.wow64_context(thread.Teb);
+                }
+            }
+
+            Ok(threads)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn thread_names(&self) -> Result<&[ThreadName], ParseError> {
+        cached(&self.thread_names, || {
+            let loc = self.stream_location(StreamType::ThreadNamesStream)?;
+            parse::parse_thread_names(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn thread_infos(&self) -> Result<&[ThreadInfo], ParseError> {
+        cached(&self.thread_infos, || {
+            let loc = self.stream_location(StreamType::ThreadInfoListStream)?;
+            parse::parse_thread_info_list(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn modules(&self) -> Result<&[Module], ParseError> {
+        cached(&self.modules, || {
+            let loc = self.stream_location(StreamType::ModuleListStream)?;
+            parse::parse_module_list(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn unloaded_modules(&self) -> Result<&[UnloadedModule], ParseError> {
+        cached(&self.unloaded_modules, || {
+            let loc = self.stream_location(StreamType::UnloadedModuleListStream)?;
+            parse::parse_unloaded_module_list(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    // Find the module containing an address. Loaded modules take priority,
+    // followed by dynamic function tables and then the most recently unloaded
+    // module covering the address.
+    pub fn lookup_address(&self, addr: u64) -> Option<AddressLocation> {
+        let contains = |base: u64, size: u32| addr >= base && addr - base < size as u64;
+
+        let modules = self.modules().unwrap_or(&[]);
+        if let Some(m) = modules
+            .iter()
+            .find(|m| contains(m.BaseOfImage, m.SizeOfImage))
+        {
+            return Some(AddressLocation::Module {
+                ModuleName: m.ModuleName.clone(),
+                BaseOfImage: m.BaseOfImage,
+                Offset: addr - m.BaseOfImage,
+            });
+        }
+
+        if let Some(table) = self.function_table(addr) {
+            return Some(AddressLocation::FunctionTable {
+                MinimumAddress: table.MinimumAddress,
+                Offset: addr - table.MinimumAddress,
+            });
+        }
+
+        let unloaded = self.unloaded_modules().unwrap_or(&[]);
+        if let Some(m) = unloaded
+            .iter()
+            .rev()
+            .find(|m| contains(m.BaseOfImage, m.SizeOfImage))
+        {
+            return Some(AddressLocation::UnloadedModule {
+                ModuleName: m.ModuleName.clone(),
+                BaseOfImage: m.BaseOfImage,
+                Offset: addr - m.BaseOfImage,
+            });
+        }
+
+        None
+    }
+
+    // Function tables registered at runtime, typically by JIT compilers
+    pub fn function_tables(&self) -> Result<&[FunctionTable], ParseError> {
+        cached(&self.function_tables, || {
+            let loc = self.stream_location(StreamType::FunctionTableStream)?;
+            parse::parse_function_table_stream(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    // Find the dynamic function table covering an address
+    pub fn function_table(&self, addr: u64) -> Option<&FunctionTable> {
+        self.function_tables()
+            .unwrap_or(&[])
+            .iter()
+            .find(|table| table.contains(addr))
+    }
+
+    // Random access reader over the captured memory ranges
+    pub fn memory_reader(&self) -> Result<&MemoryReader<'a>, ParseError> {
+        cached(&self.memory_reader, || {
+            let ranges = self.memory_list()?;
+            Ok(MemoryReader::new(self.data, ranges, self.is_64bit()))
+        })
+    }
+
+    // A 32-bit process running under WOW64 on x64 Windows. The dump has x64
+    // thread contexts, but loads wow64cpu.dll and has a 32-bit main module.
+    pub fn is_wow64(&self) -> bool {
+        match self.system_info() {
+            Ok(info) if info.ProcessorArchitecture == PROCESSOR_ARCHITECTURE_AMD64 => (),
+            _ => return false,
+        }
+
+        let modules = self.modules().unwrap_or(&[]);
+        let has_wow64cpu = modules.iter().any(|m| {
+            m.ModuleName
+                .as_ref()
+                .and_then(|name| name.rsplit('\\').next())
+                .is_some_and(|name| name.eq_ignore_ascii_case("wow64cpu.dll"))
+        });
+
+        let main_module_x86 =
+            modules.first().and_then(|m| self.module_machine(m)) == Some(IMAGE_FILE_MACHINE_I386);
+
+        has_wow64cpu && main_module_x86
+    }
+
+    // Machine type from the PE header of a module, if captured in memory
+    fn module_machine(&self, module: &Module) -> Option<u16> {
+        let memory = self.memory_reader().ok()?;
+        let base = module.BaseOfImage;
+
+        let e_lfanew = memory.read_u32(base + 0x3c).ok()?;
+        let header = memory.read_bytes(base + e_lfanew as u64, 6).ok()?;
+        if &header[0..4] != b"PE\0\0" {
+            return None;
+        }
+
+        Some(LittleEndian::read_u16(&header[4..6]))
+    }
+
+    // Find the emulated x86 context of a WOW64 thread. The TEB64 TLS slot
+    // WOW64_TLS_CPURESERVED points to the WOW64_CPURESERVED header, which the
+    // WOW64_CONTEXT follows.
+    fn wow64_context(&self, teb: u64) -> Option<ContextX86> {
+        let memory = self.memory_reader().ok()?;
+        let cpu_reserved = memory.read_u64(teb + 0x1488).ok()?;
+
+        /* struct WOW64_CPURESERVED {
+            USHORT  Flags;
+            USHORT  Machine;
+        } */
+
+        let header = memory.read_bytes(cpu_reserved, 4).ok()?;
+        if LittleEndian::read_u16(&header[2..4]) != IMAGE_FILE_MACHINE_I386 {
+            return None;
+        }
+
+        let raw = memory.read_bytes(cpu_reserved + 4, 716).ok()?;
+        let loc = LocationDescriptor {
+            Offset: 0,
+            Length: raw.len() as u64,
+        };
+        parse::parse_thread_context_x86(&raw, &loc).ok().map(|(v, _)| v)
+    }
+
+    // Captured memory ranges from both the MemoryList and Memory64List streams
+    pub fn memory_list(&self) -> Result<&[OverlayDescriptor], ParseError> {
+        cached(&self.memory_list, || {
+            if !self.has_stream(StreamType::MemoryListStream)
+                && !self.has_stream(StreamType::Memory64ListStream)
+            {
+                return Err(ParseError::MissingStream(StreamType::MemoryListStream));
+            }
+
+            let mut ranges = Vec::new();
+
+            if let Some(loc) = self.streams.get(&StreamType::MemoryListStream) {
+                let (mut v, _) = parse::parse_memory_list(self.data, loc)?;
+                ranges.append(&mut v);
+            }
+
+            if let Some(loc) = self.streams.get(&StreamType::Memory64ListStream) {
+                let (mut v, _) = parse::parse_memory64_list(self.data, loc)?;
+                ranges.append(&mut v);
+            }
+
+            Ok(ranges)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn memory_info(&self) -> Result<&[MemoryInfo], ParseError> {
+        cached(&self.memory_info, || {
+            let loc = self.stream_location(StreamType::MemoryInfoListStream)?;
+            parse::parse_memory_info(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn handles(&self) -> Result<&[HandleDescriptor], ParseError> {
+        cached(&self.handles, || {
+            let loc = self.stream_location(StreamType::HandleDataStream)?;
+            parse::parse_handle_data(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    // Handle open and close operations recorded by Application Verifier
+    pub fn handle_operations(&self) -> Result<&[HandleOperation], ParseError> {
+        cached(&self.handle_operations, || {
+            let loc = self.stream_location(StreamType::HandleOperationListStream)?;
+            parse::parse_handle_operation_list(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    // Handle operations grouped by handle value, in their recorded order
+    pub fn handle_operations_by_handle(
+        &self,
+    ) -> Result<BTreeMap<u64, Vec<&HandleOperation>>, ParseError> {
+        let mut map = BTreeMap::new();
+
+        for operation in self.handle_operations()? {
+            map.entry(operation.Handle)
+                .or_insert_with(Vec::new)
+                .push(operation);
+        }
+
+        Ok(map)
+    }
+
+    pub fn tokens(&self) -> Result<&[TokenInfo], ParseError> {
+        cached(&self.tokens, || {
+            let loc = self.stream_location(StreamType::TokenStream)?;
+            parse::parse_token_stream(self.data, loc, self.is_64bit()).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn exception(&self) -> Result<&ExceptionStream, ParseError> {
+        cached(&self.exception, || {
+            let loc = self.stream_location(StreamType::ExceptionStream)?;
+            let (mut exception, _) =
+                parse::parse_exception_stream(self.data, loc, self.context_info())?;
+
+            if self.is_wow64() {
+                let teb = self
+                    .threads()
+                    .ok()
+                    .and_then(|threads| threads.iter().find(|t| t.ThreadId == exception.ThreadId))
+                    .map(|thread| thread.Teb);
+                exception.Wow64Context = teb.and_then(|teb| self.wow64_context(teb));
+            }
+
+            Ok(exception)
+        })
+    }
+
+    pub fn system_info(&self) -> Result<&SystemInfo, ParseError> {
+        cached(&self.system_info, || {
+            let loc = self.stream_location(StreamType::SystemInfoStream)?;
+            parse::parse_system_info(self.data, loc).map(|(v, _)| v)
+        })
+    }
+
+    pub fn system_memory_info(&self) -> Result<&SystemMemoryInfo, ParseError> {
+        cached(&self.system_memory_info, || {
+            let loc = self.stream_location(StreamType::SystemMemoryInfoStream)?;
+            parse::parse_system_memory_info(self.data, loc).map(|(v, _)| v)
+        })
+    }
+
+    pub fn process_vm_counters(&self) -> Result<&ProcessVmCounters, ParseError> {
+        cached(&self.process_vm_counters, || {
+            let loc = self.stream_location(StreamType::ProcessVmCountersStream)?;
+            parse::parse_process_vm_counters(self.data, loc).map(|(v, _)| v)
+        })
+    }
+
+    pub fn misc_info(&self) -> Result<&MiscInfo, ParseError> {
+        cached(&self.misc_info, || {
+            let loc = self.stream_location(StreamType::MiscInfoStream)?;
+            parse::parse_misc_info(self.data, loc).map(|(v, _)| v)
+        })
+    }
+
+    pub fn comment_a(&self) -> Result<&str, ParseError> {
+        cached(&self.comment_a, || {
+            let loc = self.stream_location(StreamType::CommentStreamA)?;
+            parse::parse_comment_a(self.data, loc).map(|(v, _)| v)
+        }).map(String::as_str)
+    }
+
+    pub fn comment_w(&self) -> Result<&str, ParseError> {
+        cached(&self.comment_w, || {
+            let loc = self.stream_location(StreamType::CommentStreamW)?;
+            parse::parse_comment_w(self.data, loc).map(|(v, _)| v)
+        }).map(String::as_str)
+    }
+
+    // Information from other streams used to decode thread contexts
+    fn context_info(&self) -> ContextInfo<'_> {
+        ContextInfo {
+            ProcessorArchitecture: self.system_info().ok().map(|info| info.ProcessorArchitecture),
+            XStateData: self.misc_info().ok().and_then(|info| info.XStateData.as_ref()),
+        }
+    }
+
+    // Whether the dumped process uses 64-bit pointers, judged by processor
+    // architecture. Assumed to be 64-bit if system info is unavailable.
+    pub fn is_64bit(&self) -> bool {
+        match self.system_info() {
+            Ok(info) => !matches!(
+                info.ProcessorArchitecture,
+                PROCESSOR_ARCHITECTURE_INTEL
+                    | PROCESSOR_ARCHITECTURE_MIPS
+                    | PROCESSOR_ARCHITECTURE_ARM
+                    | PROCESSOR_ARCHITECTURE_SPARC
+                    | PROCESSOR_ARCHITECTURE_RISCV
+            ),
+            Err(_) => true,
+        }
+    }
+
+    pub fn linux_cpu_info(&self) -> Result<&[BTreeMap<String, String>], ParseError> {
+        cached(&self.linux_cpu_info, || {
+            let loc = self.stream_location(StreamType::LinuxCpuInfoStream)?;
+            parse::parse_linux_cpu_info(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn linux_proc_status(&self) -> Result<&BTreeMap<String, String>, ParseError> {
+        cached(&self.linux_proc_status, || {
+            let loc = self.stream_location(StreamType::LinuxProcStatusStream)?;
+            parse::parse_linux_proc_status(self.data, loc).map(|(v, _)| v)
+        })
+    }
+
+    pub fn linux_lsb_release(&self) -> Result<&BTreeMap<String, String>, ParseError> {
+        cached(&self.linux_lsb_release, || {
+            let loc = self.stream_location(StreamType::LinuxLsbReleaseStream)?;
+            parse::parse_linux_lsb_release(self.data, loc).map(|(v, _)| v)
+        })
+    }
+
+    pub fn linux_cmd_line(&self) -> Result<&[String], ParseError> {
+        cached(&self.linux_cmd_line, || {
+            let loc = self.stream_location(StreamType::LinuxCmdLineStream)?;
+            parse::parse_linux_cmd_line(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn linux_environ(&self) -> Result<&[String], ParseError> {
+        cached(&self.linux_environ, || {
+            let loc = self.stream_location(StreamType::LinuxEnvironStream)?;
+            parse::parse_linux_environ(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn linux_auxv(&self) -> Result<&[AuxvEntry], ParseError> {
+        cached(&self.linux_auxv, || {
+            let loc = self.stream_location(StreamType::LinuxAuxvStream)?;
+            parse::parse_linux_auxv(self.data, loc, self.is_64bit()).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn linux_maps(&self) -> Result<&[LinuxMapping], ParseError> {
+        cached(&self.linux_maps, || {
+            let loc = self.stream_location(StreamType::LinuxMapsStream)?;
+            parse::parse_linux_maps(self.data, loc).map(|(v, _)| v)
+        }).map(Vec::as_slice)
+    }
+
+    pub fn linux_dso_debug(&self) -> Result<&LinuxDsoDebug, ParseError> {
+        cached(&self.linux_dso_debug, || {
+            let loc = self.stream_location(StreamType::LinuxDsoDebugStream)?;
+            parse::parse_linux_dso_debug(self.data, loc).map(|(v, _)| v)
+        })
+    }
+
+    pub fn breakpad_info(&self) -> Result<&BreakpadInfo, ParseError> {
+        cached(&self.breakpad_info, || {
+            let loc = self.stream_location(StreamType::BreakpadInfoStream)?;
+            parse::parse_breakpad_info(self.data, loc).map(|(v, _)| v)
+        })
+    }
+
+    pub fn assertion_info(&self) -> Result<&AssertionInfo, ParseError> {
+        cached(&self.assertion_info, || {
+            let loc = self.stream_location(StreamType::AssertionInfoStream)?;
+            parse::parse_assertion_info(self.data, loc).map(|(v, _)| v)
+        })
+    }
+
+    pub fn crashpad_info(&self) -> Result<&CrashpadInfo, ParseError> {
+        cached(&self.crashpad_info, || {
+            let loc = self.stream_location(StreamType::CrashpadInfoStream)?;
+            let (mut info, _) = parse::parse_crashpad_info(self.data, loc)?;
+
+            if let Ok(modules) = self.modules() {
+                for module in &mut info.ModuleList {
+                    module.ModuleName = modules
+                        .get(module.ModuleListIndex as usize)
+                        .and_then(|m| m.ModuleName.clone());
+                }
+            }
+
+            Ok(info)
+        })
+    }
+
+    // Seconds between process creation and the dump being written
+    pub fn process_uptime(&self) -> Option<u32> {
+        let created = self.misc_info().ok()?.ProcessCreateTime?;
+
+        self.header.TimeDateStamp.checked_sub(created)
+    }
+}
@@ -1,27 +1,173 @@
 #![allow(non_snake_case)]
 
 use byteorder::{ByteOrder, LittleEndian};
+use error::ParseError;
+use std::collections::BTreeMap;
 use std::slice;
-use types::{ContextX64, ContextX86, Directory, ExceptionRecord, ExceptionStream, Header,
-            LocationDescriptor, MaybeThreadContext, MemoryInfo, Module, OverlayDescriptor,
-            SystemInfo, Thread};
+use types::{AssertionInfo, AuxvEntry, BreakpadInfo, CodeViewRecord, ContextArm, ContextArm64,
+            ContextX64, ContextX86, CrashpadAnnotation, CrashpadInfo, Directory, ExceptionRecord,
+            ExceptionStream, FixedFileInfo, FloatingSaveArea, FunctionTable, Guid, HandleDescriptor,
+            HandleObjectDetails, HandleObjectInformation, HandleOperation, Header, LinuxDsoDebug,
+            LinuxLinkMap, LinuxMapping, LocationDescriptor, M128A, MaybeThreadContext, MemoryInfo,
+            MiscInfo, MiscRecord, Module, ModuleCrashpadInfo, OverlayDescriptor, ProcessVmCounters,
+            RuntimeFunction, StreamType, SystemBasicInformation, SystemBasicPerformanceInformation,
+            SystemFileCacheInformation, SystemInfo, SystemMemoryInfo, SystemPerformanceInformation,
+            SystemTime, Thread, ThreadInfo, ThreadName, TimeZoneInformation, TokenInfo,
+            TokenPrivilege, UnloadedModule, XStateConfigFeatureMscInfo, XStateFeature,
+            XStateRegisters, XmmSaveArea32};
+
+// MiscInfo Flags1 validity bits
+const MINIDUMP_MISC1_PROCESS_ID: u32 = 0x00000001;
+const MINIDUMP_MISC1_PROCESS_TIMES: u32 = 0x00000002;
+const MINIDUMP_MISC1_PROCESSOR_POWER_INFO: u32 = 0x00000004;
+const MINIDUMP_MISC3_PROCESS_INTEGRITY: u32 = 0x00000010;
+const MINIDUMP_MISC3_PROCESS_EXECUTE_FLAGS: u32 = 0x00000020;
+const MINIDUMP_MISC3_TIMEZONE: u32 = 0x00000040;
+const MINIDUMP_MISC3_PROTECTED_PROCESS: u32 = 0x00000080;
+const MINIDUMP_MISC4_BUILDSTRING: u32 = 0x00000100;
+const MINIDUMP_MISC5_PROCESS_COOKIE: u32 = 0x00000200;
+
+// ProcessVmCounters Flags validity bits
+const MINIDUMP_PROCESS_VM_COUNTERS: u16 = 0x0001;
+const MINIDUMP_PROCESS_VM_COUNTERS_VIRTUALSIZE: u16 = 0x0002;
+const MINIDUMP_PROCESS_VM_COUNTERS_EX: u16 = 0x0004;
+const MINIDUMP_PROCESS_VM_COUNTERS_EX2: u16 = 0x0008;
+const MINIDUMP_PROCESS_VM_COUNTERS_JOB: u16 = 0x0010;
+
+// MINIDUMP_HANDLE_OBJECT_INFORMATION_TYPE
+const MINI_THREAD_INFORMATION1: u32 = 1;
+const MINI_MUTANT_INFORMATION1: u32 = 2;
+const MINI_MUTANT_INFORMATION2: u32 = 3;
+const MINI_PROCESS_INFORMATION1: u32 = 4;
+const MINI_PROCESS_INFORMATION2: u32 = 5;
+const MINI_SECTION_INFORMATION1: u32 = 7;
+
+// SID identifier authority of mandatory integrity labels (S-1-16-x)
+const SECURITY_MANDATORY_LABEL_AUTHORITY: u64 = 16;
+
+// Group and privilege attribute bits
+const SE_GROUP_INTEGRITY: u32 = 0x00000020;
+const SE_PRIVILEGE_ENABLED: u32 = 0x00000002;
+
+// Well-known privilege names, indexed by LUID starting at SE_MIN_WELL_KNOWN_PRIVILEGE
+const SE_MIN_WELL_KNOWN_PRIVILEGE: u64 = 2;
+const PRIVILEGE_NAMES: [&str; 35] = [
+    "SeCreateTokenPrivilege",
+    "SeAssignPrimaryTokenPrivilege",
+    "SeLockMemoryPrivilege",
+    "SeIncreaseQuotaPrivilege",
+    "SeMachineAccountPrivilege",
+    "SeTcbPrivilege",
+    "SeSecurityPrivilege",
+    "SeTakeOwnershipPrivilege",
+    "SeLoadDriverPrivilege",
+    "SeSystemProfilePrivilege",
+    "SeSystemtimePrivilege",
+    "SeProfileSingleProcessPrivilege",
+    "SeIncreaseBasePriorityPrivilege",
+    "SeCreatePagefilePrivilege",
+    "SeCreatePermanentPrivilege",
+    "SeBackupPrivilege",
+    "SeRestorePrivilege",
+    "SeShutdownPrivilege",
+    "SeDebugPrivilege",
+    "SeAuditPrivilege",
+    "SeSystemEnvironmentPrivilege",
+    "SeChangeNotifyPrivilege",
+    "SeRemoteShutdownPrivilege",
+    "SeUndockPrivilege",
+    "SeSyncAgentPrivilege",
+    "SeEnableDelegationPrivilege",
+    "SeManageVolumePrivilege",
+    "SeImpersonatePrivilege",
+    "SeCreateGlobalPrivilege",
+    "SeTrustedCredManAccessPrivilege",
+    "SeRelabelPrivilege",
+    "SeIncreaseWorkingSetPrivilege",
+    "SeTimeZonePrivilege",
+    "SeCreateSymbolicLinkPrivilege",
+    "SeDelegateSessionUserImpersonatePrivilege",
+];
+
+// Processor architectures from MINIDUMP_SYSTEM_INFO
+pub(crate) const PROCESSOR_ARCHITECTURE_INTEL: u16 = 0;
+pub(crate) const PROCESSOR_ARCHITECTURE_MIPS: u16 = 1;
+pub(crate) const PROCESSOR_ARCHITECTURE_ARM: u16 = 5;
+pub(crate) const PROCESSOR_ARCHITECTURE_AMD64: u16 = 9;
+pub(crate) const PROCESSOR_ARCHITECTURE_ARM64: u16 = 12;
+pub(crate) const PROCESSOR_ARCHITECTURE_SPARC: u16 = 0x8001; // Breakpad
+pub(crate) const PROCESSOR_ARCHITECTURE_ARM64_OLD: u16 = 0x8003; // Breakpad
+pub(crate) const PROCESSOR_ARCHITECTURE_RISCV: u16 = 0x8005; // Breakpad
+
+// Machine types from IMAGE_FILE_HEADER
+pub(crate) const IMAGE_FILE_MACHINE_I386: u16 = 0x014c;
+
+// CPU type bits of CONTEXT::ContextFlags
+const CONTEXT_X86: u32 = 0x00010000;
+const CONTEXT_AMD64: u32 = 0x00100000;
+const CONTEXT_ARM: u32 = 0x00200000;
+const CONTEXT_ARM64: u32 = 0x00400000;
+const MD_CONTEXT_ARM: u32 = 0x40000000;
+const MD_CONTEXT_ARM_OLD: u32 = 0x00000040;
+const MD_CONTEXT_ARM64_OLD: u32 = 0x80000000;
+const CONTEXT_CPU_TYPES: u32 = CONTEXT_X86 | CONTEXT_AMD64 | CONTEXT_ARM | CONTEXT_ARM64;
+
+// Register groups of CONTEXT::ContextFlags for x86 and x64
+const CONTEXT_CONTROL: u32 = 0x00000001;
+const CONTEXT_INTEGER: u32 = 0x00000002;
+const CONTEXT_SEGMENTS: u32 = 0x00000004;
+const CONTEXT_FLOATING_POINT: u32 = 0x00000008;
+const CONTEXT_DEBUG_REGISTERS: u32 = 0x00000010;
+const CONTEXT_EXTENDED_REGISTERS: u32 = 0x00000020;
+const CONTEXT_XSTATE: u32 = 0x00000040;
+
+// XSAVE feature numbers and header bits
+const XSTATE_AVX: usize = 2;
+const XSTATE_AVX512_KMASK: usize = 5;
+const XSTATE_AVX512_ZMM_H: usize = 6;
+const XSTATE_AVX512_ZMM: usize = 7;
+const XSTATE_COMPACTION_ENABLE: u64 = 1 << 63;
+
+// Features which are 64-byte aligned in the compacted XSAVE format. The
+// alignment is reported by CPUID but not recorded in the dump, so assume it
+// for the AVX-512 features.
+const XSTATE_ALIGNED_FEATURES: u64 =
+    1 << XSTATE_AVX512_KMASK | 1 << XSTATE_AVX512_ZMM_H | 1 << XSTATE_AVX512_ZMM;
+
+// Breakpad info validity bits
+const MD_BREAKPAD_INFO_VALID_DUMP_THREAD_ID: u32 = 0x00000001;
+const MD_BREAKPAD_INFO_VALID_REQUESTING_THREAD_ID: u32 = 0x00000002;
+
+// Crashpad annotation types
+const CRASHPAD_ANNOTATION_TYPE_STRING: u16 = 1;
+
+// CodeView record signatures
+const CV_SIGNATURE_RSDS: u32 = 0x53445352; // "RSDS"
+const CV_SIGNATURE_NB10: u32 = 0x3031424E; // "NB10"
+const CV_SIGNATURE_ELF: u32 = 0x4270454C; // "LEpB" (Breakpad)
 
 pub type ParseData<'a> = &'a [u8];
-pub type ParseResult<'a, T> = Result<(T, &'a [u8]), &'static str>;
+pub type ParseResult<'a, T> = Result<(T, &'a [u8]), ParseError>;
 
 fn take(data: ParseData, len: usize) -> ParseResult<ParseData> {
     if data.len() < len {
-        return Err("Incomplete Data");
+        return Err(ParseError::IncompleteData {
+            expected: len as u64,
+            actual: data.len() as u64,
+        });
     }
 
     Ok(data.split_at(len))
 }
 
-fn seek(data: ParseData, offset: usize) -> ParseResult<ParseData> {
-    if offset >= data.len() {
-        return Err("Cannot seek to data");
+fn seek(data: ParseData, offset: u64) -> ParseResult<ParseData> {
+    if offset >= data.len() as u64 {
+        return Err(ParseError::InvalidOffset {
+            offset,
+            file_size: data.len() as u64,
+        });
     }
-    let raw = &data[offset..];
+    let raw = &data[offset as usize..];
 
     Ok(raw.split_at(raw.len()))
 }
@@ -30,11 +176,38 @@ fn seek_stream<'a>(
     data: ParseData<'a>,
     loc: &LocationDescriptor,
 ) -> ParseResult<'a, ParseData<'a>> {
-    let offset = loc.Offset as usize;
-    let len = loc.Length as usize;
+    let (seek_data, remain) = seek(data, loc.Offset)?;
 
-    let (seek_data, remain) = seek(data, offset)?;
-    take(seek_data, len).map(|(d, _)| (d, remain))
+    if (seek_data.len() as u64) < loc.Length {
+        return Err(ParseError::InvalidOffset {
+            offset: loc.Offset + loc.Length,
+            file_size: data.len() as u64,
+        });
+    }
+
+    take(seek_data, loc.Length as usize).map(|(d, _)| (d, remain))
+}
+
+// Attach the stream type and location to any error raised while parsing it
+fn in_stream<T, F>(stream_type: StreamType, loc: &LocationDescriptor, f: F) -> Result<T, ParseError>
+where
+    F: FnOnce() -> Result<T, ParseError>,
+{
+    f().map_err(|e| ParseError::Stream {
+        stream_type,
+        offset: loc.Offset,
+        length: loc.Length,
+        source: Box::new(e),
+    })
+}
+
+// Attach the index and file offset of a list entry to an error
+fn in_entry(index: usize, offset: u64, e: ParseError) -> ParseError {
+    ParseError::Entry {
+        index: index as u64,
+        offset,
+        source: Box::new(e),
+    }
 }
 
 fn list_iter(
@@ -42,13 +215,25 @@ fn list_iter(
     SizeOfHeader: u64,
     SizeOfEntry: u64,
     data: ParseData,
-) -> slice::Chunks<u8> {
-    let offset = SizeOfHeader as usize;
-    let stride = SizeOfEntry as usize;
-    let len = (SizeOfHeader + SizeOfEntry * NumberOfEntries) as usize;
+) -> Result<slice::Chunks<u8>, ParseError> {
+    if SizeOfEntry == 0 {
+        return Err(ParseError::InvalidData("Invalid list entry size"));
+    }
 
-    let body = &data[offset..len];
-    body.chunks(stride)
+    let len = SizeOfEntry
+        .checked_mul(NumberOfEntries)
+        .and_then(|n| n.checked_add(SizeOfHeader))
+        .ok_or(ParseError::UnexpectedEntryCount(NumberOfEntries))?;
+
+    if len > data.len() as u64 {
+        return Err(ParseError::IncompleteData {
+            expected: len,
+            actual: data.len() as u64,
+        });
+    }
+
+    let body = &data[SizeOfHeader as usize..len as usize];
+    Ok(body.chunks(SizeOfEntry as usize))
 }
 
 macro_rules! define_array_T {
@@ -73,6 +258,7 @@ macro_rules! define_array_T {
     };
 }
 
+define_array_T!(array_u16, u16, LittleEndian::read_u16);
 define_array_T!(array_u32, u32, LittleEndian::read_u32);
 define_array_T!(array_u64, u64, LittleEndian::read_u64);
 
@@ -93,11 +279,11 @@ pub fn parse_header(data: ParseData) -> ParseResult<Header> {
     let version = LittleEndian::read_u16(&raw[4..6]);
 
     if signature != b"MDMP" {
-        return Err("Bad header magic");
+        return Err(ParseError::BadMagic);
     }
 
     if version != 42899 {
-        return Err("Unsupported minidump version");
+        return Err(ParseError::UnsupportedVersion(version));
     }
 
     let header = Header {
@@ -182,8 +368,8 @@ fn memory_info(data: ParseData) -> ParseResult<MemoryInfo> {
     let (AllocationBase, _) = address64(&raw[8..16])?;
 
     let mem_info = MemoryInfo {
-        BaseAddress: BaseAddress,
-        AllocationBase: AllocationBase,
+        BaseAddress,
+        AllocationBase,
         AllocationProtect: LittleEndian::read_u32(&raw[16..20]),
         RegionSize: LittleEndian::read_u64(&raw[24..32]),
         State: LittleEndian::read_u32(&raw[32..36]),
@@ -198,13 +384,15 @@ pub fn parse_directory<'a>(
     data: ParseData<'a>,
     header: &Header,
 ) -> ParseResult<'a, Vec<Directory>> {
-    let rva = header.StreamDirectory as usize;
+    let rva = header.StreamDirectory as u64;
     let (mut raw, remain) = seek(data, rva)?;
 
+    let SizeOfEntry = 12; // sizeof MINIDUMP_DIRECTORY
+
     let mut vec = Vec::new();
-    vec.reserve(header.NumberOfStreams as usize);
-    for _ in 0..header.NumberOfStreams {
-        let (entry, raw_next) = directory_entry(raw)?;
+    for i in 0..header.NumberOfStreams {
+        let (entry, raw_next) = directory_entry(raw)
+            .map_err(|e| in_entry(i as usize, rva + i as u64 * SizeOfEntry, e))?;
         vec.push(entry);
         raw = raw_next;
     }
@@ -222,27 +410,37 @@ pub fn parse_memory_info<'a>(
         ULONG64 NumberOfEntries;
     } */
 
-    let (raw, remain) = seek_stream(data, loc)?;
+    in_stream(StreamType::MemoryInfoListStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 16)?;
 
-    let SizeOfHeader = LittleEndian::read_u32(&raw[0..4]) as u64;
-    let SizeOfEntry = LittleEndian::read_u32(&raw[4..8]) as u64;
-    let NumberOfEntries = LittleEndian::read_u64(&raw[8..16]);
+        let SizeOfHeader = LittleEndian::read_u32(&head[0..4]) as u64;
+        let SizeOfEntry = LittleEndian::read_u32(&head[4..8]) as u64;
+        let NumberOfEntries = LittleEndian::read_u64(&head[8..16]);
 
-    if NumberOfEntries > u32::max_value() as u64 {
-        return Err("Unexpected number of entries");
-    }
-    if SizeOfHeader + NumberOfEntries * SizeOfEntry != loc.Length {
-        return Err("Unexpected Stream size");
-    }
+        if NumberOfEntries > u32::MAX as u64 {
+            return Err(ParseError::UnexpectedEntryCount(NumberOfEntries));
+        }
+        if SizeOfEntry < 48 {
+            return Err(ParseError::InvalidData("Memory info entry too small"));
+        }
+        if SizeOfHeader + NumberOfEntries * SizeOfEntry != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader + NumberOfEntries * SizeOfEntry,
+                actual: loc.Length,
+            });
+        }
 
-    let mut vec = Vec::new();
-    vec.reserve(NumberOfEntries as usize);
-    for raw_entry in list_iter(NumberOfEntries, SizeOfHeader, SizeOfEntry, raw) {
-        let (entry, _) = memory_info(raw_entry)?;
-        vec.push(entry);
-    }
+        let entries = list_iter(NumberOfEntries, SizeOfHeader, SizeOfEntry, raw)?;
+        let mut vec = Vec::with_capacity(NumberOfEntries as usize);
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfEntry;
+            let (entry, _) = memory_info(raw_entry).map_err(|e| in_entry(i, offset, e))?;
+            vec.push(entry);
+        }
 
-    Ok((vec, remain))
+        Ok((vec, remain))
+    })
 }
 
 fn module(data: ParseData) -> ParseResult<Module> {
@@ -262,38 +460,291 @@ fn module(data: ParseData) -> ParseResult<Module> {
     let (raw, remain) = take(data, 108)?;
 
     let (BaseOfImage, _) = address64(&raw[0..8])?;
+    let (VersionInfo, _) = fixed_file_info(&raw[24..76])?;
+    let (CvRecord, _) = location(&raw[76..84])?;
+    let (MiscRecord, _) = location(&raw[84..92])?;
 
     let module = Module {
-        BaseOfImage: BaseOfImage,
+        BaseOfImage,
         SizeOfImage: LittleEndian::read_u32(&raw[8..12]),
         CheckSum: LittleEndian::read_u32(&raw[12..16]),
         TimeDateStamp: LittleEndian::read_u32(&raw[16..20]),
         ModuleNameRva: LittleEndian::read_u32(&raw[20..24]),
+        VersionInfo,
+        CvRecord,
+        MiscRecord,
 
         ModuleName: None,
+        CodeView: None,
+        Misc: None,
+        DebugIdentifier: None,
+        CodeIdentifier: None,
     };
 
     Ok((module, remain))
 }
 
-pub fn parse_string(data: ParseData, rva: u32) -> ParseResult<String> {
+fn fixed_file_info(data: ParseData) -> ParseResult<Option<FixedFileInfo>> {
+    /* struct VS_FIXEDFILEINFO {
+        DWORD dwSignature;
+        DWORD dwStrucVersion;
+        DWORD dwFileVersionMS;
+        DWORD dwFileVersionLS;
+        DWORD dwProductVersionMS;
+        DWORD dwProductVersionLS;
+        DWORD dwFileFlagsMask;
+        DWORD dwFileFlags;
+        DWORD dwFileOS;
+        DWORD dwFileType;
+        DWORD dwFileSubtype;
+        DWORD dwFileDateMS;
+        DWORD dwFileDateLS;
+    } */
+
+    let (raw, remain) = take(data, 52)?;
+    let (fields, _) = array_u32(raw, 13)?;
+
+    // Version info is only present if the signature is set
+    if fields[0] != 0xFEEF04BD {
+        return Ok((None, remain));
+    }
+
+    let version = |ms: u32, ls: u32| {
+        format!("{}.{}.{}.{}", ms >> 16, ms & 0xFFFF, ls >> 16, ls & 0xFFFF)
+    };
+
+    let info = FixedFileInfo {
+        Signature: fields[0],
+        StrucVersion: fields[1],
+        FileVersionMS: fields[2],
+        FileVersionLS: fields[3],
+        ProductVersionMS: fields[4],
+        ProductVersionLS: fields[5],
+        FileFlagsMask: fields[6],
+        FileFlags: fields[7],
+        FileOS: fields[8],
+        FileType: fields[9],
+        FileSubtype: fields[10],
+        FileDateMS: fields[11],
+        FileDateLS: fields[12],
+
+        FileVersion: version(fields[2], fields[3]),
+        ProductVersion: version(fields[4], fields[5]),
+    };
+
+    Ok((Some(info), remain))
+}
+
+fn guid(data: ParseData) -> ParseResult<Guid> {
+    /* struct GUID {
+        DWORD   Data1;
+        WORD    Data2;
+        WORD    Data3;
+        BYTE    Data4[8];
+    } */
+
+    let (raw, remain) = take(data, 16)?;
+
+    let mut Data4 = [0u8; 8];
+    Data4.copy_from_slice(&raw[8..16]);
+
+    let guid = Guid {
+        Data1: LittleEndian::read_u32(&raw[0..4]),
+        Data2: LittleEndian::read_u16(&raw[4..6]),
+        Data3: LittleEndian::read_u16(&raw[6..8]),
+        Data4,
+    };
+
+    Ok((guid, remain))
+}
+
+// Decode a NUL-terminated 8-bit string, replacing invalid UTF-8
+fn c_string(data: ParseData) -> String {
+    let len = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+
+    String::from_utf8_lossy(&data[..len]).into_owned()
+}
+
+// Decode a NUL-terminated UTF-16 string, replacing invalid data
+fn c_wstring(data: ParseData) -> String {
+    let elems: Vec<u16> = data
+        .chunks(2)
+        .filter(|c| c.len() == 2)
+        .map(LittleEndian::read_u16)
+        .take_while(|&c| c != 0)
+        .collect();
+
+    String::from_utf16_lossy(&elems)
+}
+
+fn hex_string(data: ParseData) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Comment streams hold a single NUL-terminated string filling the stream
+pub fn parse_comment_a<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, String> {
+    in_stream(StreamType::CommentStreamA, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        Ok((c_string(raw), remain))
+    })
+}
+
+pub fn parse_comment_w<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, String> {
+    in_stream(StreamType::CommentStreamW, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        Ok((c_wstring(raw), remain))
+    })
+}
+
+pub fn parse_codeview_record<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, CodeViewRecord> {
+    let (raw, remain) = seek_stream(data, loc)?;
+    let (head, body) = take(raw, 4)?;
+
+    let CvSignature = LittleEndian::read_u32(head);
+
+    let record = match CvSignature {
+        CV_SIGNATURE_RSDS => {
+            /* struct CV_INFO_PDB70 {
+                DWORD   CvSignature;
+                GUID    Signature;
+                DWORD   Age;
+                BYTE    PdbFileName[];
+            } */
+
+            let (Signature, body) = guid(body)?;
+            let (age, name) = take(body, 4)?;
+
+            CodeViewRecord::Pdb70 {
+                Signature,
+                Age: LittleEndian::read_u32(age),
+                PdbFileName: c_string(name),
+            }
+        }
+        CV_SIGNATURE_NB10 => {
+            /* struct CV_INFO_PDB20 {
+                DWORD   CvSignature;
+                LONG    Offset;
+                DWORD   Signature;
+                DWORD   Age;
+                BYTE    PdbFileName[];
+            } */
+
+            let (fields, name) = take(body, 12)?;
+
+            CodeViewRecord::Pdb20 {
+                Offset: LittleEndian::read_u32(&fields[0..4]),
+                Signature: LittleEndian::read_u32(&fields[4..8]),
+                Age: LittleEndian::read_u32(&fields[8..12]),
+                PdbFileName: c_string(name),
+            }
+        }
+        CV_SIGNATURE_ELF => {
+            /* struct MDCVInfoELF {
+                uint32_t    cv_signature;
+                uint8_t     build_id[];
+            } */
+
+            CodeViewRecord::Elf {
+                BuildId: body.to_vec(),
+            }
+        }
+        _ => CodeViewRecord::Unknown { CvSignature },
+    };
+
+    Ok((record, remain))
+}
+
+pub fn parse_misc_record<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, MiscRecord> {
+    /* struct IMAGE_DEBUG_MISC {
+        DWORD   DataType;
+        DWORD   Length;
+        BOOLEAN Unicode;
+        BYTE    Reserved[3];
+        BYTE    Data[1];
+    } */
+
+    let (raw, remain) = seek_stream(data, loc)?;
+    let (head, body) = take(raw, 12)?;
+
+    let Unicode = head[8] != 0;
+
+    let Data = if Unicode {
+        c_wstring(body)
+    } else {
+        c_string(body)
+    };
+
+    let record = MiscRecord {
+        DataType: LittleEndian::read_u32(&head[0..4]),
+        Unicode,
+        Data,
+    };
+
+    Ok((record, remain))
+}
+
+// Breakpad-style debug identifier: the PDB signature and age as hex digits. For
+// ELF modules, the first 16 bytes of the build-id are treated as a GUID.
+fn debug_identifier(record: &CodeViewRecord) -> Option<String> {
+    match *record {
+        CodeViewRecord::Pdb70 {
+            ref Signature, Age, ..
+        } => Some(format!("{}{:x}", Signature.to_string().replace('-', ""), Age)),
+        CodeViewRecord::Pdb20 { Signature, Age, .. } => Some(format!("{:08X}{:x}", Signature, Age)),
+        CodeViewRecord::Elf { ref BuildId } => {
+            let mut bytes = [0u8; 16];
+            let len = BuildId.len().min(16);
+            bytes[..len].copy_from_slice(&BuildId[..len]);
+
+            let (signature, _) = guid(&bytes).ok()?;
+            Some(format!("{}0", signature.to_string().replace('-', "")))
+        }
+        CodeViewRecord::Unknown { .. } => None,
+    }
+}
+
+// Identifier of the binary itself: the PE timestamp and image size, or the full
+// build-id for ELF modules.
+fn code_identifier(module: &Module) -> Option<String> {
+    match module.CodeView {
+        Some(CodeViewRecord::Elf { ref BuildId }) => Some(hex_string(BuildId)),
+        _ if module.TimeDateStamp != 0 || module.SizeOfImage != 0 => Some(format!(
+            "{:08X}{:x}",
+            module.TimeDateStamp, module.SizeOfImage
+        )),
+        _ => None,
+    }
+}
+
+pub fn parse_string(data: ParseData, rva: u64) -> ParseResult<String> {
     /* struct MINIDUMP_STRING {
         ULONG32 Length;
         WCHAR   Buffer[];
     } */
 
-    let (raw, remain) = seek(data, rva as usize)?;
+    let (raw, remain) = seek(data, rva)?;
+    let (head, _) = take(raw, 4)?;
 
     let SizeOfHeader = 4;
     let SizeOfEntry = 2; // sizeof WCHAR
-    let Length = LittleEndian::read_u32(&raw[0..4]) as u64 / SizeOfEntry;
+    let Length = LittleEndian::read_u32(head) as u64 / SizeOfEntry;
 
-    let mut elems = Vec::new();
-    for raw_entry in list_iter(Length, SizeOfHeader, SizeOfEntry, raw) {
-        let elem = LittleEndian::read_u16(raw_entry);
-        elems.push(elem);
-    }
-    let string = String::from_utf16(&elems).map_err(|_| "bad UTF-16 data")?;
+    let elems: Vec<u16> = list_iter(Length, SizeOfHeader, SizeOfEntry, raw)?
+        .map(LittleEndian::read_u16)
+        .collect();
+    let string = String::from_utf16(&elems).map_err(|_| ParseError::InvalidString { rva })?;
 
     Ok((string, remain))
 }
@@ -306,34 +757,127 @@ pub fn parse_module_list<'a>(
         ULONG32 NumberOfModules;
     } */
 
-    let (raw, remain) = seek_stream(data, loc)?;
+    in_stream(StreamType::ModuleListStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 4)?;
 
-    let SizeOfHeader = 4;
-    let SizeOfEntry = 108; // sizeof MINIDUMP_MODULE
-    let NumberOfModules = LittleEndian::read_u32(&raw[0..4]) as u64;
+        let SizeOfHeader = 4;
+        let SizeOfEntry = 108; // sizeof MINIDUMP_MODULE
+        let NumberOfModules = LittleEndian::read_u32(head) as u64;
 
-    if NumberOfModules > u32::max_value() as u64 {
-        return Err("Unexpected number of modules");
-    }
-    if SizeOfHeader + NumberOfModules * SizeOfEntry != loc.Length {
-        return Err("Unexpected Stream size");
-    }
+        if SizeOfHeader + NumberOfModules * SizeOfEntry != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader + NumberOfModules * SizeOfEntry,
+                actual: loc.Length,
+            });
+        }
 
-    let mut vec = Vec::new();
-    vec.reserve(NumberOfModules as usize);
-    for raw_entry in list_iter(NumberOfModules, SizeOfHeader, SizeOfEntry, raw) {
-        let (mut entry, _) = module(raw_entry)?;
+        let mut vec = Vec::with_capacity(NumberOfModules as usize);
+        let entries = list_iter(NumberOfModules, SizeOfHeader, SizeOfEntry, raw)?;
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfEntry;
+            let (mut entry, _) = module(raw_entry).map_err(|e| in_entry(i, offset, e))?;
+
+            // Look up name string
+            if entry.ModuleNameRva > 0 {
+                let (name, _) = parse_string(data, entry.ModuleNameRva as u64)
+                    .map_err(|e| in_entry(i, offset, e))?;
+                entry.ModuleName = Some(name);
+            }
+
+            // Look up debug records
+            if entry.CvRecord.Length > 0 {
+                let (record, _) = parse_codeview_record(data, &entry.CvRecord)
+                    .map_err(|e| in_entry(i, offset, e))?;
+                entry.DebugIdentifier = debug_identifier(&record);
+                entry.CodeView = Some(record);
+            }
+            if entry.MiscRecord.Length > 0 {
+                let (record, _) = parse_misc_record(data, &entry.MiscRecord)
+                    .map_err(|e| in_entry(i, offset, e))?;
+                entry.Misc = Some(record);
+            }
+            entry.CodeIdentifier = code_identifier(&entry);
 
-        // Look up name string
-        if entry.ModuleNameRva > 0 {
-            let (name, _) = parse_string(data, entry.ModuleNameRva)?;
-            entry.ModuleName = Some(name);
+            vec.push(entry);
         }
 
-        vec.push(entry);
-    }
+        Ok((vec, remain))
+    })
+}
 
-    Ok((vec, remain))
+fn unloaded_module(data: ParseData) -> ParseResult<UnloadedModule> {
+    /* struct MINIDUMP_UNLOADED_MODULE {
+        ULONG64 BaseOfImage;
+        ULONG32 SizeOfImage;
+        ULONG32 CheckSum;
+        ULONG32 TimeDateStamp;
+        RVA     ModuleNameRva;
+    } */
+
+    let (raw, remain) = take(data, 24)?;
+
+    let (BaseOfImage, _) = address64(&raw[0..8])?;
+
+    let module = UnloadedModule {
+        BaseOfImage,
+        SizeOfImage: LittleEndian::read_u32(&raw[8..12]),
+        CheckSum: LittleEndian::read_u32(&raw[12..16]),
+        TimeDateStamp: LittleEndian::read_u32(&raw[16..20]),
+        ModuleNameRva: LittleEndian::read_u32(&raw[20..24]),
+
+        ModuleName: None,
+    };
+
+    Ok((module, remain))
+}
+
+pub fn parse_unloaded_module_list<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<UnloadedModule>> {
+    /* struct MINIDUMP_UNLOADED_MODULE_LIST {
+        ULONG32 SizeOfHeader;
+        ULONG32 SizeOfEntry;
+        ULONG32 NumberOfEntries;
+    } */
+
+    in_stream(StreamType::UnloadedModuleListStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 12)?;
+
+        let SizeOfHeader = LittleEndian::read_u32(&head[0..4]) as u64;
+        let SizeOfEntry = LittleEndian::read_u32(&head[4..8]) as u64;
+        let NumberOfEntries = LittleEndian::read_u32(&head[8..12]) as u64;
+
+        if SizeOfEntry < 24 {
+            return Err(ParseError::InvalidData("Unloaded module entry too small"));
+        }
+        if SizeOfHeader + NumberOfEntries * SizeOfEntry != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader + NumberOfEntries * SizeOfEntry,
+                actual: loc.Length,
+            });
+        }
+
+        let entries = list_iter(NumberOfEntries, SizeOfHeader, SizeOfEntry, raw)?;
+        let mut vec = Vec::with_capacity(NumberOfEntries as usize);
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfEntry;
+            let (mut entry, _) = unloaded_module(raw_entry).map_err(|e| in_entry(i, offset, e))?;
+
+            // Look up name string
+            if entry.ModuleNameRva > 0 {
+                let (name, _) = parse_string(data, entry.ModuleNameRva as u64)
+                    .map_err(|e| in_entry(i, offset, e))?;
+                entry.ModuleName = Some(name);
+            }
+
+            vec.push(entry);
+        }
+
+        Ok((vec, remain))
+    })
 }
 
 fn memory_range(data: ParseData) -> ParseResult<OverlayDescriptor> {
@@ -348,7 +892,7 @@ fn memory_range(data: ParseData) -> ParseResult<OverlayDescriptor> {
     let (Address, _) = address64(&raw[0..8])?;
 
     let range = OverlayDescriptor {
-        Address: Address,
+        Address,
         Location: loc,
     };
 
@@ -363,28 +907,32 @@ pub fn parse_memory_list<'a>(
         ULONG32 NumberOfMemoryRanges;
     } */
 
-    let (raw, remain) = seek_stream(data, loc)?;
+    in_stream(StreamType::MemoryListStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 4)?;
 
-    let SizeOfHeader = 4;
-    let SizeOfEntry = 16; // sizeof MINIDUMP_MEMORY_DESCRIPTOR
-    let NumberOfMemoryRanges = LittleEndian::read_u32(&raw[0..4]) as u64;
+        let SizeOfHeader = 4;
+        let SizeOfEntry = 16; // sizeof MINIDUMP_MEMORY_DESCRIPTOR
+        let NumberOfMemoryRanges = LittleEndian::read_u32(head) as u64;
 
-    if NumberOfMemoryRanges > u32::max_value() as u64 {
-        return Err("Unexpected number of memory ranges");
-    }
-    if SizeOfHeader + NumberOfMemoryRanges * SizeOfEntry != loc.Length {
-        return Err("Unexpected Stream size");
-    }
+        if SizeOfHeader + NumberOfMemoryRanges * SizeOfEntry != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader + NumberOfMemoryRanges * SizeOfEntry,
+                actual: loc.Length,
+            });
+        }
 
-    let mut vec = Vec::new();
-    vec.reserve(NumberOfMemoryRanges as usize);
-    for raw_entry in list_iter(NumberOfMemoryRanges, SizeOfHeader, SizeOfEntry, raw) {
-        let (entry, _) = memory_range(raw_entry)?;
+        let mut vec = Vec::with_capacity(NumberOfMemoryRanges as usize);
+        let entries = list_iter(NumberOfMemoryRanges, SizeOfHeader, SizeOfEntry, raw)?;
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfEntry;
+            let (entry, _) = memory_range(raw_entry).map_err(|e| in_entry(i, offset, e))?;
 
-        vec.push(entry);
-    }
+            vec.push(entry);
+        }
 
-    Ok((vec, remain))
+        Ok((vec, remain))
+    })
 }
 
 fn memory_range64(data: ParseData, base: u64) -> ParseResult<OverlayDescriptor> {
@@ -417,33 +965,43 @@ pub fn parse_memory64_list<'a>(
         RVA64   BaseRva;
     } */
 
-    let (raw, remain) = seek_stream(data, loc)?;
+    in_stream(StreamType::Memory64ListStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 16)?;
 
-    let SizeOfHeader = 16;
-    let SizeOfEntry = 16; // sizeof MINIDUMP_MEMORY_DESCRIPTOR64
-    let NumberOfMemoryRanges = LittleEndian::read_u64(&raw[0..8]);
-    let mut BaseRva = LittleEndian::read_u64(&raw[8..16]);
+        let SizeOfHeader = 16;
+        let SizeOfEntry = 16; // sizeof MINIDUMP_MEMORY_DESCRIPTOR64
+        let NumberOfMemoryRanges = LittleEndian::read_u64(&head[0..8]);
+        let mut BaseRva = LittleEndian::read_u64(&head[8..16]);
 
-    if NumberOfMemoryRanges > u32::max_value() as u64 {
-        return Err("Unexpected number of memory ranges");
-    }
-    if SizeOfHeader + NumberOfMemoryRanges * SizeOfEntry != loc.Length {
-        return Err("Unexpected Stream size");
-    }
+        if NumberOfMemoryRanges > u32::MAX as u64 {
+            return Err(ParseError::UnexpectedEntryCount(NumberOfMemoryRanges));
+        }
+        if SizeOfHeader + NumberOfMemoryRanges * SizeOfEntry != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader + NumberOfMemoryRanges * SizeOfEntry,
+                actual: loc.Length,
+            });
+        }
 
-    let mut vec = Vec::new();
-    vec.reserve(NumberOfMemoryRanges as usize);
-    for raw_entry in list_iter(NumberOfMemoryRanges, SizeOfHeader, SizeOfEntry, raw) {
-        let (entry, _) = memory_range64(raw_entry, BaseRva)?;
+        let mut vec = Vec::with_capacity(NumberOfMemoryRanges as usize);
+        let entries = list_iter(NumberOfMemoryRanges, SizeOfHeader, SizeOfEntry, raw)?;
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfEntry;
+            let (entry, _) =
+                memory_range64(raw_entry, BaseRva).map_err(|e| in_entry(i, offset, e))?;
 
-        // Memory64 data is stored contiguously at end of file so RVA of a chunk
-        // is BaseRva plus size of all chunks before.
-        BaseRva += entry.Location.Length;
+            // Memory64 data is stored contiguously at end of file so RVA of a chunk
+            // is BaseRva plus size of all chunks before.
+            BaseRva = BaseRva.checked_add(entry.Location.Length).ok_or_else(|| {
+                in_entry(i, offset, ParseError::InvalidData("Memory range overflows file"))
+            })?;
 
-        vec.push(entry);
-    }
+            vec.push(entry);
+        }
 
-    Ok((vec, remain))
+        Ok((vec, remain))
+    })
 }
 
 fn thread(data: ParseData) -> ParseResult<Thread> {
@@ -468,16 +1026,95 @@ fn thread(data: ParseData) -> ParseResult<Thread> {
         SuspendCount: LittleEndian::read_u32(&raw[4..8]),
         PriorityClass: LittleEndian::read_u32(&raw[8..12]),
         Priority: LittleEndian::read_u32(&raw[12..16]),
-        Teb: Teb,
+        Teb,
         Stack: stack,
         ThreadContext: context,
+        BackingStore: None,
 
         Context: MaybeThreadContext::None,
+        ContextError: None,
+        Info: None,
+        Name: None,
+
+        Wow64Context: None,
     };
 
     Ok((thread, remain))
 }
 
+fn floating_save_area(data: ParseData) -> ParseResult<FloatingSaveArea> {
+    /* struct FLOATING_SAVE_AREA {
+        DWORD   ControlWord;
+        DWORD   StatusWord;
+        DWORD   TagWord;
+        DWORD   ErrorOffset;
+        DWORD   ErrorSelector;
+        DWORD   DataOffset;
+        DWORD   DataSelector;
+        BYTE    RegisterArea[80];
+        DWORD   Cr0NpxState;
+    } */
+
+    let (raw, remain) = take(data, 112)?;
+    let (fields, _) = array_u32(raw, 7)?;
+
+    let area = FloatingSaveArea {
+        ControlWord: fields[0],
+        StatusWord: fields[1],
+        TagWord: fields[2],
+        ErrorOffset: fields[3],
+        ErrorSelector: fields[4],
+        DataOffset: fields[5],
+        DataSelector: fields[6],
+        RegisterArea: raw[28..108].to_vec(),
+        Cr0NpxState: LittleEndian::read_u32(&raw[108..112]),
+    };
+
+    Ok((area, remain))
+}
+
+fn xmm_save_area32(data: ParseData, NumberOfXmmRegisters: usize) -> ParseResult<XmmSaveArea32> {
+    /* struct XMM_SAVE_AREA32 {
+        WORD    ControlWord;
+        WORD    StatusWord;
+        BYTE    TagWord;
+        BYTE    Reserved1;
+        WORD    ErrorOpcode;
+        DWORD   ErrorOffset;
+        WORD    ErrorSelector;
+        WORD    Reserved2;
+        DWORD   DataOffset;
+        WORD    DataSelector;
+        WORD    Reserved3;
+        DWORD   MxCsr;
+        DWORD   MxCsr_Mask;
+        M128A   FloatRegisters[8];
+        M128A   XmmRegisters[16];
+        BYTE    Reserved4[96];
+    } */
+
+    let (raw, remain) = take(data, 512)?;
+    let (FloatRegisters, _) = vector_registers(&raw[32..160], 8)?;
+    let (XmmRegisters, _) = vector_registers(&raw[160..416], NumberOfXmmRegisters)?;
+
+    let area = XmmSaveArea32 {
+        ControlWord: LittleEndian::read_u16(&raw[0..2]),
+        StatusWord: LittleEndian::read_u16(&raw[2..4]),
+        TagWord: raw[4],
+        ErrorOpcode: LittleEndian::read_u16(&raw[6..8]),
+        ErrorOffset: LittleEndian::read_u32(&raw[8..12]),
+        ErrorSelector: LittleEndian::read_u16(&raw[12..14]),
+        DataOffset: LittleEndian::read_u32(&raw[16..20]),
+        DataSelector: LittleEndian::read_u16(&raw[20..22]),
+        MxCsr: LittleEndian::read_u32(&raw[24..28]),
+        MxCsr_Mask: LittleEndian::read_u32(&raw[28..32]),
+        FloatRegisters,
+        XmmRegisters,
+    };
+
+    Ok((area, remain))
+}
+
 pub fn parse_thread_context_x86<'a>(
     data: ParseData<'a>,
     loc: &LocationDescriptor,
@@ -512,24 +1149,53 @@ pub fn parse_thread_context_x86<'a>(
     let (raw, remain) = seek_stream(data, loc)?;
 
     let SizeOfHeader = 716;
-    if SizeOfHeader != loc.Length {
-        return Err("Unexpected Stream size");
+    if loc.Length < SizeOfHeader {
+        return Err(ParseError::UnexpectedStreamSize {
+            expected: SizeOfHeader,
+            actual: loc.Length,
+        });
     }
 
-    let (regs, _) = array_u32(&raw[156..204], 12)?;
+    let ContextFlags = LittleEndian::read_u32(&raw[0..4]);
+    let (debug, _) = array_u32(&raw[4..28], 6)?;
+    let (float_save, _) = floating_save_area(&raw[28..140])?;
+    let (regs, _) = array_u32(&raw[140..204], 16)?;
+    let (extended, _) = xmm_save_area32(&raw[204..716], 8)?;
+
+    let control = ContextFlags & CONTEXT_CONTROL != 0;
+    let integer = ContextFlags & CONTEXT_INTEGER != 0;
+    let segments = ContextFlags & CONTEXT_SEGMENTS != 0;
+    let floating_point = ContextFlags & CONTEXT_FLOATING_POINT != 0;
+    let debug_registers = ContextFlags & CONTEXT_DEBUG_REGISTERS != 0;
+    let extended_registers = ContextFlags & CONTEXT_EXTENDED_REGISTERS != 0;
 
     // NOTE: Fields listed in parse order
     let context = ContextX86 {
-        Edi: regs[0],
-        Esi: regs[1],
-        Ebx: regs[2],
-        Edx: regs[3],
-        Ecx: regs[4],
-        Eax: regs[5],
-        Ebp: regs[6],
-        Eip: regs[7],
-        EFlags: regs[9],
-        Esp: regs[10],
+        ContextFlags,
+        Dr0: debug_registers.then_some(debug[0]),
+        Dr1: debug_registers.then_some(debug[1]),
+        Dr2: debug_registers.then_some(debug[2]),
+        Dr3: debug_registers.then_some(debug[3]),
+        Dr6: debug_registers.then_some(debug[4]),
+        Dr7: debug_registers.then_some(debug[5]),
+        FloatSave: floating_point.then_some(float_save),
+        SegGs: segments.then_some(regs[0]),
+        SegFs: segments.then_some(regs[1]),
+        SegEs: segments.then_some(regs[2]),
+        SegDs: segments.then_some(regs[3]),
+        Edi: integer.then_some(regs[4]),
+        Esi: integer.then_some(regs[5]),
+        Ebx: integer.then_some(regs[6]),
+        Edx: integer.then_some(regs[7]),
+        Ecx: integer.then_some(regs[8]),
+        Eax: integer.then_some(regs[9]),
+        Ebp: control.then_some(regs[10]),
+        Eip: control.then_some(regs[11]),
+        SegCs: control.then_some(regs[12]),
+        EFlags: control.then_some(regs[13]),
+        Esp: control.then_some(regs[14]),
+        SegSs: control.then_some(regs[15]),
+        ExtendedRegisters: extended_registers.then_some(extended),
     };
 
     Ok((context, remain))
@@ -538,6 +1204,7 @@ pub fn parse_thread_context_x86<'a>(
 pub fn parse_thread_context_x64<'a>(
     data: ParseData<'a>,
     loc: &LocationDescriptor,
+    xstate: Option<&XStateConfigFeatureMscInfo>,
 ) -> ParseResult<'a, ContextX64> {
     /* struct CONTEXT {
         // +0000: Register parameter home addresses
@@ -578,148 +1245,2155 @@ pub fn parse_thread_context_x64<'a>(
 
     let (raw, remain) = seek_stream(data, loc)?;
 
+    // With CONTEXT_XSTATE, a CONTEXT_EX and the XSAVE area follow the CONTEXT
     let SizeOfHeader = 1232;
-    if SizeOfHeader != loc.Length {
-        return Err("Unexpected Stream size");
+    if loc.Length < SizeOfHeader {
+        return Err(ParseError::UnexpectedStreamSize {
+            expected: SizeOfHeader,
+            actual: loc.Length,
+        });
     }
 
+    let ContextFlags = LittleEndian::read_u32(&raw[48..52]);
+    let extended_state = ContextFlags & CONTEXT_XSTATE != 0 && loc.Length > SizeOfHeader;
+    let MxCsr = LittleEndian::read_u32(&raw[52..56]);
+    let (segs, _) = array_u16(&raw[56..68], 6)?;
     let eflags = LittleEndian::read_u32(&raw[68..72]);
+    let (debug, _) = array_u64(&raw[72..120], 6)?;
     let (regs, _) = array_u64(&raw[120..256], 17)?;
+    let (flt_save, _) = xmm_save_area32(&raw[256..768], 16)?;
+    let (branch, _) = array_u64(&raw[1192..1232], 5)?;
+
+    let control = ContextFlags & CONTEXT_CONTROL != 0;
+    let integer = ContextFlags & CONTEXT_INTEGER != 0;
+    let segments = ContextFlags & CONTEXT_SEGMENTS != 0;
+    let floating_point = ContextFlags & CONTEXT_FLOATING_POINT != 0;
+    let debug_registers = ContextFlags & CONTEXT_DEBUG_REGISTERS != 0;
+
+    // Extended state is best-effort so that a malformed XSAVE area does not
+    // hide the rest of the context
+    let XState = if extended_state {
+        xstate_registers(raw, &flt_save.XmmRegisters, xstate).ok()
+    } else {
+        None
+    };
 
     // NOTE: Fields listed in parse order
     let context = ContextX64 {
-        EFlags: eflags,
-        Rax: regs[0],
-        Rcx: regs[1],
-        Rdx: regs[2],
-        Rbx: regs[3],
-        Rsp: regs[4],
-        Rbp: regs[5],
-        Rsi: regs[6],
-        Rdi: regs[7],
-        R8: regs[8],
-        R9: regs[9],
-        R10: regs[10],
-        R11: regs[11],
-        R12: regs[12],
-        R13: regs[13],
-        R14: regs[14],
-        R15: regs[15],
-        Rip: regs[16],
+        ContextFlags,
+        MxCsr: floating_point.then_some(MxCsr),
+        SegCs: control.then_some(segs[0]),
+        SegDs: segments.then_some(segs[1]),
+        SegEs: segments.then_some(segs[2]),
+        SegFs: segments.then_some(segs[3]),
+        SegGs: segments.then_some(segs[4]),
+        SegSs: control.then_some(segs[5]),
+        EFlags: control.then_some(eflags),
+        Dr0: debug_registers.then_some(debug[0]),
+        Dr1: debug_registers.then_some(debug[1]),
+        Dr2: debug_registers.then_some(debug[2]),
+        Dr3: debug_registers.then_some(debug[3]),
+        Dr6: debug_registers.then_some(debug[4]),
+        Dr7: debug_registers.then_some(debug[5]),
+        Rax: integer.then_some(regs[0]),
+        Rcx: integer.then_some(regs[1]),
+        Rdx: integer.then_some(regs[2]),
+        Rbx: integer.then_some(regs[3]),
+        Rsp: control.then_some(regs[4]),
+        Rbp: integer.then_some(regs[5]),
+        Rsi: integer.then_some(regs[6]),
+        Rdi: integer.then_some(regs[7]),
+        R8: integer.then_some(regs[8]),
+        R9: integer.then_some(regs[9]),
+        R10: integer.then_some(regs[10]),
+        R11: integer.then_some(regs[11]),
+        R12: integer.then_some(regs[12]),
+        R13: integer.then_some(regs[13]),
+        R14: integer.then_some(regs[14]),
+        R15: integer.then_some(regs[15]),
+        Rip: control.then_some(regs[16]),
+        FltSave: floating_point.then_some(flt_save),
+        DebugControl: debug_registers.then_some(branch[0]),
+        LastBranchToRip: debug_registers.then_some(branch[1]),
+        LastBranchFromRip: debug_registers.then_some(branch[2]),
+        LastExceptionToRip: debug_registers.then_some(branch[3]),
+        LastExceptionFromRip: debug_registers.then_some(branch[4]),
+        XState,
     };
 
     Ok((context, remain))
 }
 
-fn thread_context<'a>(
-    data: ParseData<'a>,
-    loc: &LocationDescriptor,
-) -> ParseResult<'a, MaybeThreadContext> {
-    // Decode X86 CONTEXT
-    if loc.Length == 716 {
-        let (context, remain) = parse_thread_context_x86(data, loc)?;
-        return Ok((MaybeThreadContext::X86(context), remain));
+// Standard (non-compacted) offsets and sizes of XSAVE features, indexed by
+// feature number. Offsets are from the start of the XSAVE area.
+const XSTATE_STANDARD_FEATURES: [(u32, u32); 8] = [
+    (0, 160),     // x87
+    (160, 256),   // SSE
+    (576, 256),   // AVX
+    (960, 64),    // MPX BNDREGS
+    (1024, 64),   // MPX BNDCSR
+    (1088, 64),   // AVX-512 opmask
+    (1152, 512),  // AVX-512 ZMM_Hi256
+    (1664, 1024), // AVX-512 Hi16_ZMM
+];
+
+fn xstate_registers(
+    context: ParseData,
+    xmm: &[M128A],
+    config: Option<&XStateConfigFeatureMscInfo>,
+) -> Result<XStateRegisters, ParseError> {
+    /* struct CONTEXT_EX {
+        CONTEXT_CHUNK   All;
+        CONTEXT_CHUNK   Legacy;
+        CONTEXT_CHUNK   XState;
     }
 
-    // Decode X64 CONTEXT
-    if loc.Length == 1232 {
-        let (context, remain) = parse_thread_context_x64(data, loc)?;
-        return Ok((MaybeThreadContext::X64(context), remain));
+    struct CONTEXT_CHUNK {
+        LONG    Offset; // Relative to CONTEXT_EX
+        DWORD   Length;
     }
 
-    Ok((MaybeThreadContext::None, data))
-}
-
-pub fn parse_thread_list<'a>(
-    data: ParseData<'a>,
-    loc: &LocationDescriptor,
-) -> ParseResult<'a, Vec<Thread>> {
-    /* struct MINIDUMP_THREAD_LIST {
-        ULONG32 NumberOfThreads;
+    struct XSAVE_AREA_HEADER {
+        DWORD64 Mask;
+        DWORD64 CompactionMask;
+        DWORD64 Reserved2[6];
     } */
 
-    let (raw, remain) = seek_stream(data, loc)?;
-
-    let SizeOfHeader = 4;
-    let SizeOfEntry = 48; // sizeof MINIDUMP_THREAD
-    let NumberOfThreads = LittleEndian::read_u32(&raw[0..4]) as u64;
+    let SizeOfContext = 1232;
+    let (ex, _) = take(&context[SizeOfContext..], 24)?;
 
-    if NumberOfThreads > u32::max_value() as u64 {
-        return Err("Unexpected number of threads");
-    }
-    if SizeOfHeader + NumberOfThreads * SizeOfEntry != loc.Length {
-        return Err("Unexpected Stream size");
+    let offset = SizeOfContext as i64 + LittleEndian::read_i32(&ex[16..20]) as i64;
+    let length = LittleEndian::read_u32(&ex[20..24]) as usize;
+    if offset < 0 || offset as usize > context.len() {
+        return Err(ParseError::InvalidData("Invalid XState chunk offset"));
     }
 
-    let mut vec = Vec::new();
-    vec.reserve(NumberOfThreads as usize);
-    for raw_entry in list_iter(NumberOfThreads, SizeOfHeader, SizeOfEntry, raw) {
-        let (mut entry, _) = thread(raw_entry)?;
+    let (area, _) = take(&context[offset as usize..], length)?;
+    let (header, _) = take(area, 64)?;
 
-        let (context, _) = thread_context(data, &entry.ThreadContext)?;
-        entry.Context = context;
+    let Mask = LittleEndian::read_u64(&header[0..8]);
+    let CompactionMask = LittleEndian::read_u64(&header[8..16]);
+    let compacted = CompactionMask & XSTATE_COMPACTION_ENABLE != 0;
 
-        vec.push(entry);
-    }
+    let feature_size = |i: usize| match config {
+        Some(config) if config.Features[i].Size != 0 => config.Features[i].Size,
+        _ => XSTATE_STANDARD_FEATURES[i].1,
+    };
 
-    Ok((vec, remain))
-}
+    // Offset of a feature from the XSAVE header. In compacted form, features
+    // which were saved are packed in order after the header, with some
+    // starting on a 64-byte boundary.
+    let feature_offset = |i: usize| -> Option<usize> {
+        if compacted {
+            if CompactionMask & (1 << i) == 0 {
+                return None;
+            }
+            let mut offset: usize = 64;
+            for j in (2..=i).filter(|&j| CompactionMask & (1 << j) != 0) {
+                if XSTATE_ALIGNED_FEATURES & (1 << j) != 0 {
+                    offset = offset.checked_add(63)? & !63;
+                }
+                if j == i {
+                    break;
+                }
+                offset = offset.checked_add(feature_size(j) as usize)?;
+            }
+            return Some(offset);
+        }
 
-fn exception_record_32(data: ParseData) -> ParseResult<ExceptionRecord> {
-    /* struct EXCEPTION_RECORD32 {
-        DWORD       ExceptionCode;
-        DWORD       ExceptionFlags;
-        DWORD       ExceptionRecord;
-        DWORD       ExceptionAddress;
-        DWORD       NumberParameters;
-        DWORD       ExceptionInformation[EXCEPTION_MAXIMUM_PARAMETERS];
-    } */
+        match config {
+            Some(config) if config.EnabledFeatures & (1 << i) == 0 => None,
+            Some(config) if config.Features[i].Offset >= 512 => {
+                Some(config.Features[i].Offset as usize - 512)
+            }
+            _ => Some(XSTATE_STANDARD_FEATURES[i].0 as usize - 512),
+        }
+    };
 
-    let (raw, remain) = take(data, 80)?;
+    // Feature state is None if it was not captured. Features which are not
+    // in Mask are in their initial state, which is all zeros.
+    let feature = |i: usize| -> Option<Vec<u8>> {
+        let offset = feature_offset(i)?;
+        let raw = area.get(offset..offset.checked_add(feature_size(i) as usize)?)?;
+        if Mask & (1 << i) != 0 {
+            Some(raw.to_vec())
+        } else {
+            Some(vec![0; raw.len()])
+        }
+    };
 
-    let ExceptionRecord = LittleEndian::read_u32(&raw[8..12]);
-    if ExceptionRecord != 0 {
-        return Err("Unexpected exception chain");
-    }
+    let avx = match feature(XSTATE_AVX) {
+        Some(raw) => Some(vector_registers(&raw, 16)?.0),
+        None => None,
+    };
+    let opmask = match feature(XSTATE_AVX512_KMASK) {
+        Some(raw) => Some(array_u64(&raw, 8)?.0),
+        None => None,
+    };
+    let zmm_h = match feature(XSTATE_AVX512_ZMM_H) {
+        Some(raw) => Some(vector_registers(&raw, 32)?.0),
+        None => None,
+    };
+    let zmm = match feature(XSTATE_AVX512_ZMM) {
+        Some(raw) => Some(vector_registers(&raw, 64)?.0),
+        None => None,
+    };
 
-    let NumberParameters = LittleEndian::read_u32(&raw[16..20]) as usize;
-    if NumberParameters > 15 {
-        return Err("Invalid number of exception parameters");
-    }
+    // YMM registers extend XMM registers, and ZMM registers extend YMM
+    let Ymm = avx.as_ref().map(|high| {
+        xmm.iter()
+            .zip(high)
+            .map(|(&low, &high)| [low, high])
+            .collect::<Vec<_>>()
+    });
+
+    let Zmm = match (&Ymm, zmm_h, zmm) {
+        (Some(ymm), Some(zmm_h), Some(zmm)) => {
+            let low = ymm
+                .iter()
+                .zip(zmm_h.chunks(2))
+                .map(|(ymm, high)| [ymm[0], ymm[1], high[0], high[1]]);
+            let high = zmm.chunks(4).map(|zmm| [zmm[0], zmm[1], zmm[2], zmm[3]]);
+
+            Some(low.chain(high).collect())
+        }
+        _ => None,
+    };
 
-    let (Information, _) = array_u32(&raw[20..], NumberParameters)?;
+    Ok(XStateRegisters {
+        Mask,
+        CompactionMask,
+        Ymm,
+        OpMask: opmask,
+        Zmm,
+    })
+}
 
-    let rec = ExceptionRecord {
-        Code: LittleEndian::read_u32(&raw[0..4]),
-        Flags: LittleEndian::read_u32(&raw[4..8]),
-        Address: LittleEndian::read_u32(&raw[12..16]) as u64,
-        Information: Information,
-    };
+fn vector_registers(data: ParseData, count: usize) -> ParseResult<Vec<M128A>> {
+    let (raw, remain) = take(data, count * 16)?;
 
-    Ok((rec, remain))
+    let regs = raw
+        .chunks(16)
+        .map(|raw_reg| M128A {
+            Low: LittleEndian::read_u64(&raw_reg[0..8]),
+            High: LittleEndian::read_u64(&raw_reg[8..16]),
+        })
+        .collect();
+
+    Ok((regs, remain))
 }
 
-fn exception_record_64(data: ParseData) -> ParseResult<ExceptionRecord> {
-    /* struct EXCEPTION_RECORD64 {
-        DWORD       ExceptionCode;
-        DWORD       ExceptionFlags;
-        DWORD64     ExceptionRecord;
-        DWORD64     ExceptionAddress;
-        DWORD       NumberParameters;
-        DWORD       __unusedAlignment;
-        DWORD64     ExceptionInformation[EXCEPTION_MAXIMUM_PARAMETERS];
+pub fn parse_thread_context_arm64<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, ContextArm64> {
+    /* struct ARM64_NT_CONTEXT {
+        DWORD           ContextFlags;
+        DWORD           Cpsr;
+
+        // +0008: Integer registers
+        DWORD64         X[31];  // X29 is Fp, X30 is Lr
+        DWORD64         Sp;
+        DWORD64         Pc;
+
+        // +0272: Floating point state
+        ARM64_NT_NEON128 V[32];
+        DWORD           Fpcr;
+        DWORD           Fpsr;
+
+        // +0792: Debug registers
+        DWORD           Bcr[8];
+        DWORD64         Bvr[8];
+        DWORD           Wcr[2];
+        DWORD64         Wvr[2];
     } */
 
-    let (raw, remain) = take(data, 152)?;
+    let (raw, remain) = seek_stream(data, loc)?;
 
-    let ExceptionRecord = LittleEndian::read_u64(&raw[8..16]);
-    if ExceptionRecord != 0 {
-        return Err("Unexpected exception chain");
+    let SizeOfHeader = 912;
+    if loc.Length < SizeOfHeader {
+        return Err(ParseError::UnexpectedStreamSize {
+            expected: SizeOfHeader,
+            actual: loc.Length,
+        });
     }
 
-    let NumberParameters = LittleEndian::read_u32(&raw[24..28]) as usize;
-    if NumberParameters > 15 {
-        return Err("Invalid number of exception parameters");
+    let (regs, _) = array_u64(&raw[8..272], 33)?;
+    let (V, _) = vector_registers(&raw[272..784], 32)?;
+
+    let context = ContextArm64 {
+        ContextFlags: LittleEndian::read_u32(&raw[0..4]),
+        Cpsr: LittleEndian::read_u32(&raw[4..8]),
+        X: regs[0..29].to_vec(),
+        Fp: regs[29],
+        Lr: regs[30],
+        Sp: regs[31],
+        Pc: regs[32],
+        Fpcr: LittleEndian::read_u32(&raw[784..788]),
+        Fpsr: LittleEndian::read_u32(&raw[788..792]),
+        V,
+    };
+
+    Ok((context, remain))
+}
+
+// Breakpad wrote its own packed layout before adopting ARM64_NT_CONTEXT
+pub fn parse_thread_context_arm64_old<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, ContextArm64> {
+    /* struct MDRawContextARM64_Old {
+        uint64_t    context_flags;
+
+        // +0008: Integer registers
+        uint64_t    iregs[32];  // iregs[31] is Sp
+        uint64_t    pc;
+        uint32_t    cpsr;
+
+        // +0276: Floating point state
+        uint32_t    fpsr;
+        uint32_t    fpcr;
+        uint128_t   regs[32];
+    } */
+
+    let (raw, remain) = seek_stream(data, loc)?;
+
+    let SizeOfHeader = 796;
+    if loc.Length < SizeOfHeader {
+        return Err(ParseError::UnexpectedStreamSize {
+            expected: SizeOfHeader,
+            actual: loc.Length,
+        });
+    }
+
+    let (regs, _) = array_u64(&raw[8..272], 33)?;
+    let (V, _) = vector_registers(&raw[284..796], 32)?;
+
+    let context = ContextArm64 {
+        ContextFlags: LittleEndian::read_u32(&raw[0..4]),
+        Cpsr: LittleEndian::read_u32(&raw[272..276]),
+        X: regs[0..29].to_vec(),
+        Fp: regs[29],
+        Lr: regs[30],
+        Sp: regs[31],
+        Pc: regs[32],
+        Fpcr: LittleEndian::read_u32(&raw[280..284]),
+        Fpsr: LittleEndian::read_u32(&raw[276..280]),
+        V,
+    };
+
+    Ok((context, remain))
+}
+
+pub fn parse_thread_context_arm<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, ContextArm> {
+    /* struct CONTEXT {
+        DWORD           ContextFlags;
+
+        // +0004: Integer registers
+        DWORD           R0, R1, R2, R3, R4, R5, R6, R7, R8, R9, R10, R11, R12;
+
+        // +0056: Control registers
+        DWORD           Sp;
+        DWORD           Lr;
+        DWORD           Pc;
+        DWORD           Cpsr;
+
+        // +0072: Floating point state
+        DWORD           Fpscr;
+        DWORD           Padding;
+        ULONGLONG       D[32];
+
+        // +0336: Debug registers
+        DWORD           Bvr[8];
+        DWORD           Bcr[8];
+        DWORD           Wvr[1];
+        DWORD           Wcr[1];
+        DWORD           Padding2[2];
+    } */
+
+    let (raw, remain) = seek_stream(data, loc)?;
+
+    let SizeOfHeader = 416;
+    if loc.Length < SizeOfHeader {
+        return Err(ParseError::UnexpectedStreamSize {
+            expected: SizeOfHeader,
+            actual: loc.Length,
+        });
+    }
+
+    let (regs, _) = array_u32(&raw[4..72], 17)?;
+    let (D, _) = array_u64(&raw[80..336], 32)?;
+
+    let context = ContextArm {
+        ContextFlags: LittleEndian::read_u32(&raw[0..4]),
+        Cpsr: regs[16],
+        Pc: regs[15],
+        Sp: regs[13],
+        Lr: regs[14],
+        R: regs[0..13].to_vec(),
+        Fpscr: LittleEndian::read_u32(&raw[72..76]),
+        D,
+    };
+
+    Ok((context, remain))
+}
+
+pub fn parse_thread_context_arm_breakpad<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, ContextArm> {
+    /* struct MDRawContextARM {
+        uint32_t    context_flags;
+
+        // +0004: Integer registers
+        uint32_t    iregs[16];  // iregs[13..15] are Sp, Lr, Pc
+        uint32_t    cpsr;
+
+        // +0072: Floating point state
+        uint64_t    fpscr;
+        uint64_t    regs[32];
+        uint32_t    extra[8];
+    } */
+
+    let (raw, remain) = seek_stream(data, loc)?;
+
+    let SizeOfHeader = 368;
+    if loc.Length < SizeOfHeader {
+        return Err(ParseError::UnexpectedStreamSize {
+            expected: SizeOfHeader,
+            actual: loc.Length,
+        });
+    }
+
+    let (regs, _) = array_u32(&raw[4..72], 17)?;
+    let (D, _) = array_u64(&raw[80..336], 32)?;
+
+    let context = ContextArm {
+        ContextFlags: LittleEndian::read_u32(&raw[0..4]),
+        Cpsr: regs[16],
+        Pc: regs[15],
+        Sp: regs[13],
+        Lr: regs[14],
+        R: regs[0..13].to_vec(),
+        Fpscr: LittleEndian::read_u32(&raw[72..76]),
+        D,
+    };
+
+    Ok((context, remain))
+}
+
+// Dump-wide information used to decode thread contexts
+#[derive(Clone, Copy)]
+pub struct ContextInfo<'a> {
+    pub ProcessorArchitecture: Option<u16>,
+    pub XStateData: Option<&'a XStateConfigFeatureMscInfo>,
+}
+
+// Thread context layouts
+#[derive(Clone, Copy, PartialEq)]
+enum ContextLayout {
+    X86,
+    X64,
+    Arm,
+    ArmBreakpad,
+    Arm64,
+    Arm64Old,
+}
+
+// Does ContextFlags carry the CPU type bit of a context layout? The x64
+// CONTEXT stores its flags after the register home area rather than first.
+fn context_flags_match(layout: ContextLayout, ContextFlags: u32, ContextFlagsX64: u32) -> bool {
+    match layout {
+        ContextLayout::X86 => ContextFlags & CONTEXT_X86 != 0,
+        ContextLayout::X64 => ContextFlagsX64 & CONTEXT_AMD64 != 0,
+        ContextLayout::Arm => ContextFlags & CONTEXT_ARM != 0,
+        ContextLayout::ArmBreakpad => ContextFlags & MD_CONTEXT_ARM != 0,
+        ContextLayout::Arm64 => ContextFlags & CONTEXT_ARM64 != 0,
+        ContextLayout::Arm64Old => ContextFlags & MD_CONTEXT_ARM64_OLD != 0,
+    }
+}
+
+// Select the context layout from the processor architecture, using the
+// ContextFlags CPU type bits to choose between layouts for the same
+// architecture. Without system info the flags alone are used. Returns None if
+// the layout cannot be determined.
+fn context_layout(raw: ParseData, info: ContextInfo) -> Result<Option<ContextLayout>, ParseError> {
+    use self::ContextLayout::*;
+
+    let flags_at =
+        |offset: usize| raw.get(offset..offset + 4).map(LittleEndian::read_u32).unwrap_or(0);
+    let ContextFlags = flags_at(0);
+    let ContextFlagsX64 = if raw.len() >= 1232 { flags_at(48) } else { 0 };
+    let matches =
+        |layout: &&ContextLayout| context_flags_match(**layout, ContextFlags, ContextFlagsX64);
+
+    // Windows layouts are checked before Breakpad layouts, since Breakpad
+    // reuses bits which Windows assigns to exception reporting.
+    let all = [X64, Arm64, Arm, X86, ArmBreakpad, Arm64Old];
+
+    let arch = match info.ProcessorArchitecture {
+        Some(arch) => arch,
+        None => return Ok(all.iter().find(matches).cloned()),
+    };
+
+    // Layouts valid for each architecture. The first is assumed when the
+    // flags do not name a CPU type.
+    let valid: &[ContextLayout] = match arch {
+        PROCESSOR_ARCHITECTURE_INTEL => &[X86],
+        PROCESSOR_ARCHITECTURE_AMD64 => &[X64],
+        PROCESSOR_ARCHITECTURE_ARM => &[Arm, ArmBreakpad],
+        PROCESSOR_ARCHITECTURE_ARM64 => &[Arm64, Arm64Old],
+        PROCESSOR_ARCHITECTURE_ARM64_OLD => &[Arm64Old, Arm64],
+        _ => return Ok(None),
+    };
+
+    if let Some(&layout) = valid.iter().find(matches) {
+        return Ok(Some(layout));
+    }
+
+    // An x64 CONTEXT starts with register home values rather than its flags,
+    // so it is recognized as another CPU by its size or the CPU type bits of
+    // its own flags.
+    if arch == PROCESSOR_ARCHITECTURE_AMD64 {
+        if raw.len() < 1232 {
+            return Err(ParseError::ContextMismatch {
+                architecture: arch,
+                context_flags: ContextFlags,
+            });
+        }
+        if ContextFlagsX64 & CONTEXT_CPU_TYPES != 0 {
+            return Err(ParseError::ContextMismatch {
+                architecture: arch,
+                context_flags: ContextFlagsX64,
+            });
+        }
+    } else if all.iter().filter(|&&layout| layout != X64).any(|layout| matches(&layout)) {
+        return Err(ParseError::ContextMismatch {
+            architecture: arch,
+            context_flags: ContextFlags,
+        });
+    }
+
+    // Early Breakpad ARM contexts used a different CPU type flag
+    if arch == PROCESSOR_ARCHITECTURE_ARM && ContextFlags & MD_CONTEXT_ARM_OLD != 0 {
+        return Ok(Some(ArmBreakpad));
+    }
+
+    Ok(Some(valid[0]))
+}
+
+// Decode a thread context. Contexts may be padded beyond the size of their
+// structure, and an empty location means no context was saved.
+fn thread_context<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+    info: ContextInfo,
+) -> ParseResult<'a, MaybeThreadContext> {
+    let (raw, remain) = seek_stream(data, loc)?;
+    if raw.is_empty() {
+        return Ok((MaybeThreadContext::None, remain));
+    }
+
+    let context = match context_layout(raw, info)? {
+        Some(ContextLayout::X86) => {
+            MaybeThreadContext::X86(Box::new(parse_thread_context_x86(data, loc)?.0))
+        }
+        Some(ContextLayout::X64) => {
+            let (context, _) = parse_thread_context_x64(data, loc, info.XStateData)?;
+            MaybeThreadContext::X64(Box::new(context))
+        }
+        Some(ContextLayout::Arm) => MaybeThreadContext::Arm(parse_thread_context_arm(data, loc)?.0),
+        Some(ContextLayout::ArmBreakpad) => {
+            MaybeThreadContext::Arm(parse_thread_context_arm_breakpad(data, loc)?.0)
+        }
+        Some(ContextLayout::Arm64) => {
+            MaybeThreadContext::Arm64(parse_thread_context_arm64(data, loc)?.0)
+        }
+        Some(ContextLayout::Arm64Old) => {
+            MaybeThreadContext::Arm64(parse_thread_context_arm64_old(data, loc)?.0)
+        }
+        None => MaybeThreadContext::None,
+    };
+
+    Ok((context, remain))
+}
+
+// Decode a thread context. A context which cannot be decoded, such as one for
+// a different CPU to the dump, is reported rather than failing the stream so
+// that other contexts can still be used.
+fn checked_thread_context(
+    data: ParseData,
+    loc: &LocationDescriptor,
+    info: ContextInfo,
+) -> (MaybeThreadContext, Option<String>) {
+    match thread_context(data, loc, info) {
+        Ok((context, _)) => (context, None),
+        Err(e) => (MaybeThreadContext::None, Some(e.to_string())),
+    }
+}
+
+fn thread_ex(data: ParseData) -> ParseResult<Thread> {
+    /* struct MINIDUMP_THREAD_EX {
+        ...
+        MINIDUMP_LOCATION_DESCRIPTOR    ThreadContext;
+        MINIDUMP_MEMORY_DESCRIPTOR      BackingStore;
+    } */
+
+    let (mut thread, remain) = thread(data)?;
+    let (store, remain) = memory_range(remain)?;
+
+    thread.BackingStore = Some(store);
+
+    Ok((thread, remain))
+}
+
+pub fn parse_thread_list<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+    info: ContextInfo,
+) -> ParseResult<'a, Vec<Thread>> {
+    /* struct MINIDUMP_THREAD_LIST {
+        ULONG32 NumberOfThreads;
+    } */
+
+    let SizeOfEntry = 48; // sizeof MINIDUMP_THREAD
+    in_stream(StreamType::ThreadListStream, loc, || {
+        thread_list(data, loc, info, SizeOfEntry, thread)
+    })
+}
+
+pub fn parse_thread_ex_list<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+    info: ContextInfo,
+) -> ParseResult<'a, Vec<Thread>> {
+    /* struct MINIDUMP_THREAD_EX_LIST {
+        ULONG32 NumberOfThreads;
+    } */
+
+    let SizeOfEntry = 64; // sizeof MINIDUMP_THREAD_EX
+    in_stream(StreamType::ThreadExListStream, loc, || {
+        thread_list(data, loc, info, SizeOfEntry, thread_ex)
+    })
+}
+
+// Common parsing of thread lists, which differ only in their entry type
+fn thread_list<'a, F>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+    info: ContextInfo,
+    SizeOfEntry: u64,
+    parse_entry: F,
+) -> ParseResult<'a, Vec<Thread>>
+where
+    F: Fn(ParseData) -> ParseResult<Thread>,
+{
+    let (raw, remain) = seek_stream(data, loc)?;
+    let (head, _) = take(raw, 4)?;
+
+    let SizeOfHeader = 4;
+    let NumberOfThreads = LittleEndian::read_u32(head) as u64;
+
+    if SizeOfHeader + NumberOfThreads * SizeOfEntry != loc.Length {
+        return Err(ParseError::UnexpectedStreamSize {
+            expected: SizeOfHeader + NumberOfThreads * SizeOfEntry,
+            actual: loc.Length,
+        });
+    }
+
+    let mut vec = Vec::with_capacity(NumberOfThreads as usize);
+    let entries = list_iter(NumberOfThreads, SizeOfHeader, SizeOfEntry, raw)?;
+    for (i, raw_entry) in entries.enumerate() {
+        let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfEntry;
+        let (mut entry, _) = parse_entry(raw_entry).map_err(|e| in_entry(i, offset, e))?;
+
+        let (context, error) = checked_thread_context(data, &entry.ThreadContext, info);
+        entry.Context = context;
+        entry.ContextError = error;
+
+        vec.push(entry);
+    }
+
+    Ok((vec, remain))
+}
+
+fn thread_info(data: ParseData) -> ParseResult<ThreadInfo> {
+    /* struct MINIDUMP_THREAD_INFO {
+        ULONG32 ThreadId;
+        ULONG32 DumpFlags;
+        ULONG32 DumpError;
+        ULONG32 ExitStatus;
+        ULONG64 CreateTime;
+        ULONG64 ExitTime;
+        ULONG64 KernelTime;
+        ULONG64 UserTime;
+        ULONG64 StartAddress;
+        ULONG64 Affinity;
+    } */
+
+    let (raw, remain) = take(data, 64)?;
+
+    let (StartAddress, _) = address64(&raw[48..56])?;
+
+    let info = ThreadInfo {
+        ThreadId: LittleEndian::read_u32(&raw[0..4]),
+        DumpFlags: LittleEndian::read_u32(&raw[4..8]),
+        DumpError: LittleEndian::read_u32(&raw[8..12]),
+        ExitStatus: LittleEndian::read_u32(&raw[12..16]),
+        CreateTime: LittleEndian::read_u64(&raw[16..24]),
+        ExitTime: LittleEndian::read_u64(&raw[24..32]),
+        KernelTime: LittleEndian::read_u64(&raw[32..40]),
+        UserTime: LittleEndian::read_u64(&raw[40..48]),
+        StartAddress,
+        Affinity: LittleEndian::read_u64(&raw[56..64]),
+    };
+
+    Ok((info, remain))
+}
+
+pub fn parse_thread_info_list<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<ThreadInfo>> {
+    /* struct MINIDUMP_THREAD_INFO_LIST {
+        ULONG SizeOfHeader;
+        ULONG SizeOfEntry;
+        ULONG NumberOfEntries;
+    } */
+
+    in_stream(StreamType::ThreadInfoListStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 12)?;
+
+        let SizeOfHeader = LittleEndian::read_u32(&head[0..4]) as u64;
+        let SizeOfEntry = LittleEndian::read_u32(&head[4..8]) as u64;
+        let NumberOfEntries = LittleEndian::read_u32(&head[8..12]) as u64;
+
+        if SizeOfEntry < 64 {
+            return Err(ParseError::InvalidData("Thread info entry too small"));
+        }
+        if SizeOfHeader + NumberOfEntries * SizeOfEntry != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader + NumberOfEntries * SizeOfEntry,
+                actual: loc.Length,
+            });
+        }
+
+        let entries = list_iter(NumberOfEntries, SizeOfHeader, SizeOfEntry, raw)?;
+        let mut vec = Vec::with_capacity(NumberOfEntries as usize);
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfEntry;
+            let (entry, _) = thread_info(raw_entry).map_err(|e| in_entry(i, offset, e))?;
+            vec.push(entry);
+        }
+
+        Ok((vec, remain))
+    })
+}
+
+fn thread_name(data: ParseData) -> ParseResult<ThreadName> {
+    /* struct MINIDUMP_THREAD_NAME {
+        ULONG   ThreadId;
+        RVA64   RvaOfThreadName;
+    } */
+
+    let (raw, remain) = take(data, 12)?;
+
+    let name = ThreadName {
+        ThreadId: LittleEndian::read_u32(&raw[0..4]),
+        RvaOfThreadName: LittleEndian::read_u64(&raw[4..12]),
+
+        Name: String::new(),
+    };
+
+    Ok((name, remain))
+}
+
+pub fn parse_thread_names<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<ThreadName>> {
+    /* struct MINIDUMP_THREAD_NAME_LIST {
+        ULONG NumberOfThreadNames;
+    } */
+
+    in_stream(StreamType::ThreadNamesStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 4)?;
+
+        let SizeOfHeader = 4;
+        let SizeOfEntry = 12; // sizeof MINIDUMP_THREAD_NAME
+        let NumberOfThreadNames = LittleEndian::read_u32(head) as u64;
+
+        if SizeOfHeader + NumberOfThreadNames * SizeOfEntry != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader + NumberOfThreadNames * SizeOfEntry,
+                actual: loc.Length,
+            });
+        }
+
+        let mut vec = Vec::with_capacity(NumberOfThreadNames as usize);
+        let entries = list_iter(NumberOfThreadNames, SizeOfHeader, SizeOfEntry, raw)?;
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfEntry;
+            let (mut entry, _) = thread_name(raw_entry).map_err(|e| in_entry(i, offset, e))?;
+
+            // Look up name string
+            let (name, _) = parse_string(data, entry.RvaOfThreadName)
+                .map_err(|e| in_entry(i, offset, e))?;
+            entry.Name = name;
+
+            vec.push(entry);
+        }
+
+        Ok((vec, remain))
+    })
+}
+
+// Read a pointer-sized value from native structures of either width
+fn native_ptr(data: ParseData, offset: usize, wide: bool) -> u64 {
+    if wide {
+        LittleEndian::read_u64(&data[offset..offset + 8])
+    } else {
+        LittleEndian::read_u32(&data[offset..offset + 4]) as u64
+    }
+}
+
+fn handle_object_details(InfoType: u32, data: ParseData) -> Option<HandleObjectDetails> {
+    // Only decode when the size matches a known 32-bit or 64-bit layout
+    let details = match (InfoType, data.len()) {
+        (MINI_THREAD_INFORMATION1, len @ 28) | (MINI_THREAD_INFORMATION1, len @ 48) => {
+            /* struct THREAD_BASIC_INFORMATION {
+                NTSTATUS  ExitStatus;
+                PVOID     TebBaseAddress;
+                CLIENT_ID ClientId;
+                KAFFINITY AffinityMask;
+                KPRIORITY Priority;
+                KPRIORITY BasePriority;
+            } */
+            let wide = len == 48;
+            let p = if wide { 8 } else { 4 };
+            let base = if wide { 8 } else { 4 };
+            HandleObjectDetails::Thread {
+                ExitStatus: LittleEndian::read_u32(&data[0..4]),
+                TebBaseAddress: native_ptr(data, base, wide),
+                ProcessId: native_ptr(data, base + p, wide),
+                ThreadId: native_ptr(data, base + 2 * p, wide),
+                AffinityMask: native_ptr(data, base + 3 * p, wide),
+                Priority: LittleEndian::read_i32(&data[base + 4 * p..]),
+                BasePriority: LittleEndian::read_i32(&data[base + 4 * p + 4..]),
+            }
+        }
+        (MINI_MUTANT_INFORMATION1, 8) => {
+            /* struct MUTANT_BASIC_INFORMATION {
+                LONG    CurrentCount;
+                BOOLEAN OwnedByCaller;
+                BOOLEAN AbandonedState;
+            } */
+            HandleObjectDetails::Mutant {
+                CurrentCount: LittleEndian::read_i32(&data[0..4]),
+                OwnedByCaller: data[4] != 0,
+                AbandonedState: data[5] != 0,
+            }
+        }
+        (MINI_MUTANT_INFORMATION2, len @ 8) | (MINI_MUTANT_INFORMATION2, len @ 16) => {
+            /* struct MUTANT_OWNER_INFORMATION {
+                CLIENT_ID ClientId;
+            } */
+            let wide = len == 16;
+            HandleObjectDetails::MutantOwner {
+                ProcessId: native_ptr(data, 0, wide),
+                ThreadId: native_ptr(data, len / 2, wide),
+            }
+        }
+        (MINI_PROCESS_INFORMATION1, len @ 24)
+        | (MINI_PROCESS_INFORMATION1, len @ 48)
+        | (MINI_PROCESS_INFORMATION2, len @ 24)
+        | (MINI_PROCESS_INFORMATION2, len @ 48) => {
+            /* struct PROCESS_BASIC_INFORMATION {
+                NTSTATUS  ExitStatus;
+                PPEB      PebBaseAddress;
+                KAFFINITY AffinityMask;
+                KPRIORITY BasePriority;
+                HANDLE    UniqueProcessId;
+                HANDLE    InheritedFromUniqueProcessId;
+            } */
+            let wide = len == 48;
+            let p = if wide { 8 } else { 4 };
+            HandleObjectDetails::Process {
+                ExitStatus: LittleEndian::read_u32(&data[0..4]),
+                PebBaseAddress: native_ptr(data, p, wide),
+                AffinityMask: native_ptr(data, 2 * p, wide),
+                BasePriority: LittleEndian::read_i32(&data[3 * p..]),
+                ProcessId: native_ptr(data, 4 * p, wide),
+                ParentProcessId: native_ptr(data, 5 * p, wide),
+            }
+        }
+        (MINI_SECTION_INFORMATION1, len @ 16) | (MINI_SECTION_INFORMATION1, len @ 24) => {
+            /* struct SECTION_BASIC_INFORMATION {
+                PVOID         BaseAddress;
+                ULONG         AllocationAttributes;
+                LARGE_INTEGER MaximumSize;
+            } */
+            let wide = len == 24;
+            let p = if wide { 8 } else { 4 };
+            HandleObjectDetails::Section {
+                BaseAddress: native_ptr(data, 0, wide),
+                AllocationAttributes: LittleEndian::read_u32(&data[p..p + 4]),
+                MaximumSize: LittleEndian::read_u64(&data[len - 8..]),
+            }
+        }
+        _ => return None,
+    };
+
+    Some(details)
+}
+
+fn handle_object_information(data: ParseData, rva: u32) -> ParseResult<HandleObjectInformation> {
+    /* struct MINIDUMP_HANDLE_OBJECT_INFORMATION {
+        RVA     NextInfoRva;
+        ULONG32 InfoType;
+        ULONG32 SizeOfInfo;
+    } */
+
+    let (raw, _) = seek(data, rva as u64)?;
+    let (head, raw) = take(raw, 12)?;
+
+    let InfoType = LittleEndian::read_u32(&head[4..8]);
+    let SizeOfInfo = LittleEndian::read_u32(&head[8..12]);
+
+    let (info, remain) = take(raw, SizeOfInfo as usize)?;
+
+    let info = HandleObjectInformation {
+        NextInfoRva: LittleEndian::read_u32(&head[0..4]),
+        InfoType,
+        SizeOfInfo,
+        Data: info.to_vec(),
+
+        Details: handle_object_details(InfoType, info),
+    };
+
+    Ok((info, remain))
+}
+
+// Follow the chain of object information records for a handle
+fn handle_object_info_chain(
+    data: ParseData,
+    rva: u32,
+) -> Result<Vec<HandleObjectInformation>, ParseError> {
+    let mut vec = Vec::new();
+    let mut seen = Vec::new();
+
+    let mut next = rva;
+    while next != 0 {
+        if seen.contains(&next) {
+            return Err(ParseError::InvalidData("Loop in handle object information chain"));
+        }
+        seen.push(next);
+
+        let (info, _) = handle_object_information(data, next)?;
+        next = info.NextInfoRva;
+        vec.push(info);
+    }
+
+    Ok(vec)
+}
+
+fn handle_descriptor(data: ParseData, SizeOfDescriptor: u64) -> ParseResult<HandleDescriptor> {
+    /* struct MINIDUMP_HANDLE_DESCRIPTOR {
+        ULONG64 Handle;
+        RVA     TypeNameRva;
+        RVA     ObjectNameRva;
+        ULONG32 Attributes;
+        ULONG32 GrantedAccess;
+        ULONG32 HandleCount;
+        ULONG32 PointerCount;
+    } */
+
+    /* struct MINIDUMP_HANDLE_DESCRIPTOR_2 {
+        ...
+        RVA     ObjectInfoRva;
+        ULONG32 Reserved0;
+    } */
+
+    let (raw, remain) = take(data, SizeOfDescriptor as usize)?;
+
+    let ObjectInfoRva = if SizeOfDescriptor >= 40 {
+        LittleEndian::read_u32(&raw[32..36])
+    } else {
+        0
+    };
+
+    let handle = HandleDescriptor {
+        Handle: LittleEndian::read_u64(&raw[0..8]),
+        TypeNameRva: LittleEndian::read_u32(&raw[8..12]),
+        ObjectNameRva: LittleEndian::read_u32(&raw[12..16]),
+        Attributes: LittleEndian::read_u32(&raw[16..20]),
+        GrantedAccess: LittleEndian::read_u32(&raw[20..24]),
+        HandleCount: LittleEndian::read_u32(&raw[24..28]),
+        PointerCount: LittleEndian::read_u32(&raw[28..32]),
+        ObjectInfoRva,
+
+        TypeName: None,
+        ObjectName: None,
+        ObjectInfo: Vec::new(),
+    };
+
+    Ok((handle, remain))
+}
+
+pub fn parse_handle_data<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<HandleDescriptor>> {
+    /* struct MINIDUMP_HANDLE_DATA_STREAM {
+        ULONG32 SizeOfHeader;
+        ULONG32 SizeOfDescriptor;
+        ULONG32 NumberOfDescriptors;
+        ULONG32 Reserved;
+    } */
+
+    in_stream(StreamType::HandleDataStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 16)?;
+
+        let SizeOfHeader = LittleEndian::read_u32(&head[0..4]) as u64;
+        let SizeOfDescriptor = LittleEndian::read_u32(&head[4..8]) as u64;
+        let NumberOfDescriptors = LittleEndian::read_u32(&head[8..12]) as u64;
+
+        // Either MINIDUMP_HANDLE_DESCRIPTOR or MINIDUMP_HANDLE_DESCRIPTOR_2
+        if SizeOfDescriptor != 32 && SizeOfDescriptor != 40 {
+            return Err(ParseError::InvalidData("Unknown handle descriptor size"));
+        }
+
+        if SizeOfHeader + NumberOfDescriptors * SizeOfDescriptor != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader + NumberOfDescriptors * SizeOfDescriptor,
+                actual: loc.Length,
+            });
+        }
+
+        let mut vec = Vec::with_capacity(NumberOfDescriptors as usize);
+        let entries = list_iter(NumberOfDescriptors, SizeOfHeader, SizeOfDescriptor, raw)?;
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfDescriptor;
+            let (mut entry, _) = handle_descriptor(raw_entry, SizeOfDescriptor)
+                .map_err(|e| in_entry(i, offset, e))?;
+
+            // Look up name strings
+            if entry.TypeNameRva != 0 {
+                let (name, _) = parse_string(data, entry.TypeNameRva as u64)
+                    .map_err(|e| in_entry(i, offset, e))?;
+                entry.TypeName = Some(name);
+            }
+            if entry.ObjectNameRva != 0 {
+                let (name, _) = parse_string(data, entry.ObjectNameRva as u64)
+                    .map_err(|e| in_entry(i, offset, e))?;
+                entry.ObjectName = Some(name);
+            }
+
+            entry.ObjectInfo = handle_object_info_chain(data, entry.ObjectInfoRva)
+                .map_err(|e| in_entry(i, offset, e))?;
+
+            vec.push(entry);
+        }
+
+        Ok((vec, remain))
+    })
+}
+
+// Parse a SID, which is sized by its sub-authority count
+fn sid(data: ParseData) -> ParseResult<String> {
+    /* struct SID {
+        BYTE                        Revision;
+        BYTE                        SubAuthorityCount;
+        SID_IDENTIFIER_AUTHORITY    IdentifierAuthority;
+        DWORD                       SubAuthority[ANYSIZE_ARRAY];
+    } */
+
+    let (head, _) = take(data, 8)?;
+    if head[0] != 1 || head[1] > 15 {
+        return Err(ParseError::InvalidData("Unknown SID revision"));
+    }
+
+    let (raw, remain) = take(data, 8 + 4 * head[1] as usize)?;
+
+    // Identifier authority is a 48-bit big-endian value
+    let authority = raw[2..8].iter().fold(0u64, |acc, &b| acc << 8 | b as u64);
+
+    let mut string = format!("S-1-{}", authority);
+    for raw in raw[8..].chunks(4) {
+        string += &format!("-{}", LittleEndian::read_u32(raw));
+    }
+
+    Ok((string, remain))
+}
+
+// Parse a SID_AND_ATTRIBUTES array as returned by GetTokenInformation. The SID
+// pointers refer to the address space of the writer, but the SIDs themselves
+// follow the array in the same order.
+fn sid_and_attributes(
+    data: ParseData,
+    count: usize,
+    wide: bool,
+) -> ParseResult<Vec<(String, u32)>> {
+    /* struct SID_AND_ATTRIBUTES {
+        PSID  Sid;
+        DWORD Attributes;
+    } */
+
+    let SizeOfPointer = if wide { 8 } else { 4 };
+    let SizeOfEntry = 2 * SizeOfPointer;
+
+    let SizeOfArray = count
+        .checked_mul(SizeOfEntry)
+        .ok_or(ParseError::UnexpectedEntryCount(count as u64))?;
+    let (raw, mut remain) = take(data, SizeOfArray)?;
+
+    let mut vec = Vec::with_capacity(count);
+    for raw_entry in raw.chunks(SizeOfEntry) {
+        let Attributes = LittleEndian::read_u32(&raw_entry[SizeOfPointer..]);
+        let (Sid, rest) = sid(remain)?;
+
+        vec.push((Sid, Attributes));
+        remain = rest;
+    }
+
+    Ok((vec, remain))
+}
+
+fn token_groups(data: ParseData, wide: bool) -> ParseResult<Vec<(String, u32)>> {
+    /* struct TOKEN_GROUPS {
+        DWORD              GroupCount;
+        SID_AND_ATTRIBUTES Groups[ANYSIZE_ARRAY];
+    } */
+
+    let (raw, _) = take(data, 4)?;
+    let GroupCount = LittleEndian::read_u32(raw) as usize;
+
+    // The array is aligned for its pointers
+    let (_, raw) = take(data, if wide { 8 } else { 4 })?;
+    sid_and_attributes(raw, GroupCount, wide)
+}
+
+fn token_privileges(data: ParseData) -> ParseResult<Vec<TokenPrivilege>> {
+    /* struct TOKEN_PRIVILEGES {
+        DWORD               PrivilegeCount;
+        LUID_AND_ATTRIBUTES Privileges[ANYSIZE_ARRAY];
+    } */
+
+    let (raw, remain) = take(data, 4)?;
+    let PrivilegeCount = LittleEndian::read_u32(raw) as usize;
+
+    let SizeOfArray = PrivilegeCount
+        .checked_mul(12)
+        .ok_or(ParseError::UnexpectedEntryCount(PrivilegeCount as u64))?;
+    let (raw, remain) = take(remain, SizeOfArray)?;
+
+    let mut vec = Vec::with_capacity(PrivilegeCount);
+    for raw in raw.chunks(12) {
+        let Luid = LittleEndian::read_u64(&raw[0..8]);
+        let Attributes = LittleEndian::read_u32(&raw[8..12]);
+
+        let Name = Luid
+            .checked_sub(SE_MIN_WELL_KNOWN_PRIVILEGE)
+            .and_then(|index| PRIVILEGE_NAMES.get(index as usize))
+            .cloned();
+
+        vec.push(TokenPrivilege {
+            Luid,
+            Attributes,
+
+            Name,
+            Enabled: Attributes & SE_PRIVILEGE_ENABLED != 0,
+        });
+    }
+
+    Ok((vec, remain))
+}
+
+fn integrity_level_name(rid: u32) -> Option<&'static str> {
+    let name = match rid {
+        0x0000 => "Untrusted",
+        0x1000 => "Low",
+        0x2000 => "Medium",
+        0x2100 => "MediumPlus",
+        0x3000 => "High",
+        0x4000 => "System",
+        0x5000 => "Protected",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+// The token data holds the TOKEN_USER, TOKEN_GROUPS and TOKEN_PRIVILEGES
+// buffers, in that order, as returned by GetTokenInformation. Mandatory label
+// groups give the integrity level.
+fn decode_token(token: &mut TokenInfo, wide: bool) -> Result<(), ParseError> {
+    /* struct TOKEN_USER {
+        SID_AND_ATTRIBUTES User;
+    } */
+
+    let (mut user, remain) = sid_and_attributes(&token.Data, 1, wide)?;
+    let (groups, remain) = token_groups(remain, wide)?;
+    let (privileges, _) = token_privileges(remain)?;
+
+    let label = format!("S-1-{}-", SECURITY_MANDATORY_LABEL_AUTHORITY);
+    for (sid, Attributes) in groups {
+        match sid.strip_prefix(&label) {
+            Some(rid) if Attributes & SE_GROUP_INTEGRITY != 0 => {
+                token.IntegrityLevel = rid.parse().ok();
+            }
+            _ => token.Groups.push(sid),
+        }
+    }
+
+    token.User = user.pop().map(|(sid, _)| sid);
+    token.Privileges = privileges;
+    token.IntegrityLevelName = token.IntegrityLevel.and_then(integrity_level_name);
+
+    Ok(())
+}
+
+fn token_info(data: ParseData, ElementHeaderSize: u64, wide: bool) -> ParseResult<TokenInfo> {
+    /* struct MINIDUMP_TOKEN_INFO_HEADER {
+        ULONG32 TokenSize;
+        ULONG32 TokenId;
+        ULONG64 TokenHandle;
+    } */
+
+    let (raw, _) = take(data, 16)?;
+
+    // TokenSize covers the header and the token data that follows it
+    let TokenSize = LittleEndian::read_u32(&raw[0..4]);
+    if (TokenSize as u64) < ElementHeaderSize {
+        return Err(ParseError::InvalidData("Token entry smaller than its header"));
+    }
+
+    let (entry, remain) = take(data, TokenSize as usize)?;
+
+    let mut token = TokenInfo {
+        TokenSize,
+        TokenId: LittleEndian::read_u32(&raw[4..8]),
+        TokenHandle: LittleEndian::read_u64(&raw[8..16]),
+        Data: entry[ElementHeaderSize as usize..].to_vec(),
+
+        User: None,
+        Groups: Vec::new(),
+        Privileges: Vec::new(),
+        IntegrityLevel: None,
+        IntegrityLevelName: None,
+    };
+
+    // Token data which does not match the expected layout is left undecoded
+    let _ = decode_token(&mut token, wide);
+
+    Ok((token, remain))
+}
+
+pub fn parse_token_stream<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+    wide: bool,
+) -> ParseResult<'a, Vec<TokenInfo>> {
+    /* struct MINIDUMP_TOKEN_INFO_LIST {
+        ULONG32 TokenListSize;
+        ULONG32 TokenListEntries;
+        ULONG32 ListHeaderSize;
+        ULONG32 ElementHeaderSize;
+    } */
+
+    in_stream(StreamType::TokenStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 16)?;
+
+        let TokenListSize = LittleEndian::read_u32(&head[0..4]) as u64;
+        let TokenListEntries = LittleEndian::read_u32(&head[4..8]) as u64;
+        let ListHeaderSize = LittleEndian::read_u32(&head[8..12]) as u64;
+        let ElementHeaderSize = LittleEndian::read_u32(&head[12..16]) as u64;
+
+        if ListHeaderSize < 16 || ElementHeaderSize < 16 {
+            return Err(ParseError::InvalidData("Token list header sizes are too small"));
+        }
+        if TokenListSize > loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: TokenListSize,
+                actual: loc.Length,
+            });
+        }
+
+        // Each entry is at least a header, which bounds the number of entries
+        let SizeOfHeaders = TokenListEntries
+            .checked_mul(ElementHeaderSize)
+            .and_then(|n| n.checked_add(ListHeaderSize))
+            .ok_or(ParseError::UnexpectedEntryCount(TokenListEntries))?;
+        if SizeOfHeaders > TokenListSize {
+            return Err(ParseError::UnexpectedEntryCount(TokenListEntries));
+        }
+
+        // Entries are variable length so walk them in sequence
+        let (mut raw, _) = take(raw, TokenListSize as usize)?;
+        let (_, rest) = take(raw, ListHeaderSize as usize)?;
+        raw = rest;
+
+        let mut vec = Vec::with_capacity(TokenListEntries as usize);
+        for i in 0..TokenListEntries as usize {
+            let offset = loc.Offset + TokenListSize - raw.len() as u64;
+            let (entry, rest) =
+                token_info(raw, ElementHeaderSize, wide).map_err(|e| in_entry(i, offset, e))?;
+
+            vec.push(entry);
+            raw = rest;
+        }
+
+        Ok((vec, remain))
+    })
+}
+
+fn handle_operation(data: ParseData) -> ParseResult<HandleOperation> {
+    /* struct AVRF_HANDLE_OPERATION {
+        ULONG64 Handle;
+        ULONG   ProcessId;
+        ULONG   ThreadId;
+        ULONG   OperationType;
+        ULONG   Spare0;
+        AVRF_BACKTRACE_INFORMATION BackTraceInformation;
+    } */
+
+    /* struct AVRF_BACKTRACE_INFORMATION {
+        ULONG   Depth;
+        ULONG   Index;
+        ULONG64 ReturnAddresses[AVRF_MAX_TRACES];
+    } */
+
+    let AVRF_MAX_TRACES = 32;
+
+    let (raw, remain) = take(data, 32)?;
+    let (mut BackTrace, remain) = array_u64(remain, AVRF_MAX_TRACES)?;
+
+    let Depth = LittleEndian::read_u32(&raw[24..28]) as usize;
+    BackTrace.truncate(Depth);
+
+    let operation = HandleOperation {
+        Handle: LittleEndian::read_u64(&raw[0..8]),
+        ProcessId: LittleEndian::read_u32(&raw[8..12]),
+        ThreadId: LittleEndian::read_u32(&raw[12..16]),
+        OperationType: LittleEndian::read_u32(&raw[16..20]),
+        BackTraceIndex: LittleEndian::read_u32(&raw[28..32]),
+        BackTrace,
+    };
+
+    Ok((operation, remain))
+}
+
+pub fn parse_handle_operation_list<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<HandleOperation>> {
+    /* struct MINIDUMP_HANDLE_OPERATION_LIST {
+        ULONG32 SizeOfHeader;
+        ULONG32 SizeOfEntry;
+        ULONG32 NumberOfEntries;
+        ULONG32 Reserved;
+    } */
+
+    in_stream(StreamType::HandleOperationListStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 16)?;
+
+        let SizeOfHeader = LittleEndian::read_u32(&head[0..4]) as u64;
+        let SizeOfEntry = LittleEndian::read_u32(&head[4..8]) as u64;
+        let NumberOfEntries = LittleEndian::read_u32(&head[8..12]) as u64;
+
+        if SizeOfEntry < 288 {
+            return Err(ParseError::InvalidData("Handle operation entry too small"));
+        }
+
+        if SizeOfHeader + NumberOfEntries * SizeOfEntry != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader + NumberOfEntries * SizeOfEntry,
+                actual: loc.Length,
+            });
+        }
+
+        let mut vec = Vec::with_capacity(NumberOfEntries as usize);
+        let entries = list_iter(NumberOfEntries, SizeOfHeader, SizeOfEntry, raw)?;
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = loc.Offset + SizeOfHeader + i as u64 * SizeOfEntry;
+            let (entry, _) = handle_operation(raw_entry).map_err(|e| in_entry(i, offset, e))?;
+
+            vec.push(entry);
+        }
+
+        Ok((vec, remain))
+    })
+}
+
+fn runtime_function(data: ParseData) -> RuntimeFunction {
+    match data.len() {
+        12 => {
+            /* struct RUNTIME_FUNCTION {   // x64
+                DWORD BeginAddress;
+                DWORD EndAddress;
+                DWORD UnwindInfoAddress;
+            } */
+            RuntimeFunction::X64 {
+                BeginAddress: LittleEndian::read_u32(&data[0..4]),
+                EndAddress: LittleEndian::read_u32(&data[4..8]),
+                UnwindInfoAddress: LittleEndian::read_u32(&data[8..12]),
+            }
+        }
+        8 => {
+            /* struct RUNTIME_FUNCTION {   // ARM, ARM64
+                DWORD BeginAddress;
+                DWORD UnwindData;
+            } */
+            RuntimeFunction::Arm {
+                BeginAddress: LittleEndian::read_u32(&data[0..4]),
+                UnwindData: LittleEndian::read_u32(&data[4..8]),
+            }
+        }
+        _ => RuntimeFunction::Unknown {
+            Data: data.to_vec(),
+        },
+    }
+}
+
+fn function_table<'a>(
+    data: ParseData<'a>,
+    SizeOfNativeDescriptor: u64,
+    SizeOfFunctionEntry: u64,
+) -> ParseResult<'a, FunctionTable> {
+    /* struct MINIDUMP_FUNCTION_TABLE_DESCRIPTOR {
+        ULONG64 MinimumAddress;
+        ULONG64 MaximumAddress;
+        ULONG64 BaseAddress;
+        ULONG32 EntryCount;
+        ULONG32 SizeOfAlignPad;
+    } */
+
+    // Each descriptor is followed by the native descriptor, the function
+    // entries and then alignment padding.
+    let (raw, remain) = take(data, 32)?;
+    let (native, remain) = take(remain, SizeOfNativeDescriptor as usize)?;
+
+    let EntryCount = LittleEndian::read_u32(&raw[24..28]);
+    let SizeOfAlignPad = LittleEndian::read_u32(&raw[28..32]);
+
+    // The entry size does not matter for a descriptor without entries
+    let (Entries, remain) = if EntryCount == 0 {
+        (Vec::new(), remain)
+    } else {
+        let entries = list_iter(EntryCount as u64, 0, SizeOfFunctionEntry, remain)?;
+        let SizeOfEntries = EntryCount as usize * SizeOfFunctionEntry as usize;
+        (entries.map(runtime_function).collect(), &remain[SizeOfEntries..])
+    };
+    let (_, remain) = take(remain, SizeOfAlignPad as usize)?;
+
+    let table = FunctionTable {
+        MinimumAddress: LittleEndian::read_u64(&raw[0..8]),
+        MaximumAddress: LittleEndian::read_u64(&raw[8..16]),
+        BaseAddress: LittleEndian::read_u64(&raw[16..24]),
+        EntryCount,
+        SizeOfAlignPad,
+        NativeDescriptor: native.to_vec(),
+
+        Entries,
+    };
+
+    Ok((table, remain))
+}
+
+pub fn parse_function_table_stream<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<FunctionTable>> {
+    /* struct MINIDUMP_FUNCTION_TABLE_STREAM {
+        ULONG32 SizeOfHeader;
+        ULONG32 SizeOfDescriptor;
+        ULONG32 SizeOfNativeDescriptor;
+        ULONG32 SizeOfFunctionEntry;
+        ULONG32 NumberOfDescriptors;
+        ULONG32 SizeOfAlignPad;
+    } */
+
+    in_stream(StreamType::FunctionTableStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 24)?;
+
+        let SizeOfHeader = LittleEndian::read_u32(&head[0..4]) as u64;
+        let SizeOfDescriptor = LittleEndian::read_u32(&head[4..8]) as u64;
+        let SizeOfNativeDescriptor = LittleEndian::read_u32(&head[8..12]) as u64;
+        let SizeOfFunctionEntry = LittleEndian::read_u32(&head[12..16]) as u64;
+        let NumberOfDescriptors = LittleEndian::read_u32(&head[16..20]) as u64;
+        let SizeOfAlignPad = LittleEndian::read_u32(&head[20..24]) as u64;
+
+        if SizeOfDescriptor != 32 {
+            return Err(ParseError::InvalidData("Unknown function table descriptor size"));
+        }
+
+        // Descriptors are variable length so walk them in sequence
+        let (_, mut raw) = take(raw, (SizeOfHeader + SizeOfAlignPad) as usize)?;
+
+        // Each descriptor is at least a fixed and native descriptor
+        if NumberOfDescriptors * (SizeOfDescriptor + SizeOfNativeDescriptor) > raw.len() as u64 {
+            return Err(ParseError::UnexpectedEntryCount(NumberOfDescriptors));
+        }
+
+        let mut vec = Vec::with_capacity(NumberOfDescriptors as usize);
+        for i in 0..NumberOfDescriptors as usize {
+            let offset = loc.Offset + loc.Length - raw.len() as u64;
+            let (entry, rest) = function_table(raw, SizeOfNativeDescriptor, SizeOfFunctionEntry)
+                .map_err(|e| in_entry(i, offset, e))?;
+
+            vec.push(entry);
+            raw = rest;
+        }
+
+        Ok((vec, remain))
+    })
+}
+
+// Linux text streams are copies of files from /proc or /etc
+fn text_stream<'a>(data: ParseData<'a>, loc: &LocationDescriptor) -> ParseResult<'a, String> {
+    let (raw, remain) = seek_stream(data, loc)?;
+    Ok((String::from_utf8_lossy(raw).into_owned(), remain))
+}
+
+// Parse "Key: Value" lines, as used by /proc/cpuinfo and /proc/self/status
+fn key_value_lines(text: &str, separator: char) -> BTreeMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(separator)?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+pub fn parse_linux_cpu_info<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<BTreeMap<String, String>>> {
+    in_stream(StreamType::LinuxCpuInfoStream, loc, || {
+        let (text, remain) = text_stream(data, loc)?;
+
+        // Each processor is described by a block of lines
+        let processors = text
+            .split("\n\n")
+            .map(|block| key_value_lines(block, ':'))
+            .filter(|block| !block.is_empty())
+            .collect();
+
+        Ok((processors, remain))
+    })
+}
+
+pub fn parse_linux_proc_status<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, BTreeMap<String, String>> {
+    in_stream(StreamType::LinuxProcStatusStream, loc, || {
+        let (text, remain) = text_stream(data, loc)?;
+
+        Ok((key_value_lines(&text, ':'), remain))
+    })
+}
+
+pub fn parse_linux_lsb_release<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, BTreeMap<String, String>> {
+    in_stream(StreamType::LinuxLsbReleaseStream, loc, || {
+        let (text, remain) = text_stream(data, loc)?;
+
+        // Values may be quoted, as in /etc/os-release
+        let mut release = key_value_lines(&text, '=');
+        for value in release.values_mut() {
+            *value = value.trim_matches('"').to_string();
+        }
+
+        Ok((release, remain))
+    })
+}
+
+// Split a list of NUL-terminated strings
+fn nul_separated(data: ParseData) -> Vec<String> {
+    let data = data.strip_suffix(&[0]).unwrap_or(data);
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    data.split(|&b| b == 0)
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+        .collect()
+}
+
+pub fn parse_linux_cmd_line<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<String>> {
+    in_stream(StreamType::LinuxCmdLineStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        Ok((nul_separated(raw), remain))
+    })
+}
+
+pub fn parse_linux_environ<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<String>> {
+    in_stream(StreamType::LinuxEnvironStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        Ok((nul_separated(raw), remain))
+    })
+}
+
+fn auxv_name(Type: u64) -> Option<&'static str> {
+    let name = match Type {
+        0 => "AT_NULL",
+        1 => "AT_IGNORE",
+        2 => "AT_EXECFD",
+        3 => "AT_PHDR",
+        4 => "AT_PHENT",
+        5 => "AT_PHNUM",
+        6 => "AT_PAGESZ",
+        7 => "AT_BASE",
+        8 => "AT_FLAGS",
+        9 => "AT_ENTRY",
+        10 => "AT_NOTELF",
+        11 => "AT_UID",
+        12 => "AT_EUID",
+        13 => "AT_GID",
+        14 => "AT_EGID",
+        15 => "AT_PLATFORM",
+        16 => "AT_HWCAP",
+        17 => "AT_CLKTCK",
+        23 => "AT_SECURE",
+        24 => "AT_BASE_PLATFORM",
+        25 => "AT_RANDOM",
+        26 => "AT_HWCAP2",
+        31 => "AT_EXECFN",
+        32 => "AT_SYSINFO",
+        33 => "AT_SYSINFO_EHDR",
+        51 => "AT_MINSIGSTKSZ",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+// The auxiliary vector is a copy of /proc/self/auxv, which holds pairs of
+// native pointer-sized values terminated by AT_NULL.
+pub fn parse_linux_auxv<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+    wide: bool,
+) -> ParseResult<'a, Vec<AuxvEntry>> {
+    in_stream(StreamType::LinuxAuxvStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+
+        let SizeOfEntry = if wide { 16 } else { 8 };
+
+        let mut vec = Vec::new();
+        for raw_entry in raw.chunks_exact(SizeOfEntry) {
+            let Type = native_ptr(raw_entry, 0, wide);
+            if Type == 0 {
+                break;
+            }
+
+            vec.push(AuxvEntry {
+                Type,
+                Value: native_ptr(raw_entry, SizeOfEntry / 2, wide),
+
+                Name: auxv_name(Type),
+            });
+        }
+
+        Ok((vec, remain))
+    })
+}
+
+fn linux_mapping(line: &str) -> Result<LinuxMapping, ParseError> {
+    // start-end perms offset dev inode [path]
+    let mut fields = line.splitn(6, ' ');
+    let mut next = || fields.next().ok_or(ParseError::InvalidData("Truncated maps line"));
+
+    let range = next()?;
+    let Permissions = next()?.to_string();
+    let offset = next()?;
+    let Device = next()?.to_string();
+    let inode = next()?;
+    let path = fields.next().map(str::trim).unwrap_or("");
+
+    let hex = |s: &str| {
+        u64::from_str_radix(s, 16).map_err(|_| ParseError::InvalidData("Bad number in maps line"))
+    };
+
+    let (start, end) = range
+        .split_once('-')
+        .ok_or(ParseError::InvalidData("Bad address range in maps line"))?;
+
+    let mapping = LinuxMapping {
+        Start: hex(start)?,
+        End: hex(end)?,
+        Permissions,
+        Offset: hex(offset)?,
+        Device,
+        Inode: inode
+            .parse()
+            .map_err(|_| ParseError::InvalidData("Bad inode in maps line"))?,
+        Path: if path.is_empty() { None } else { Some(path.to_string()) },
+    };
+
+    Ok(mapping)
+}
+
+pub fn parse_linux_maps<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, Vec<LinuxMapping>> {
+    in_stream(StreamType::LinuxMapsStream, loc, || {
+        let (text, remain) = text_stream(data, loc)?;
+
+        let mut vec = Vec::new();
+        let mut offset = loc.Offset;
+        for (i, line) in text.split('\n').enumerate() {
+            if !line.trim().is_empty() {
+                let mapping = linux_mapping(line).map_err(|e| in_entry(i, offset, e))?;
+                vec.push(mapping);
+            }
+
+            offset += line.len() as u64 + 1;
+        }
+
+        Ok((vec, remain))
+    })
+}
+
+fn linux_link_map_size(wide: bool, packed: bool) -> u64 {
+    match (wide, packed) {
+        (false, _) => 12,
+        (true, true) => 20,
+        (true, false) => 24,
+    }
+}
+
+fn linux_link_map(data: ParseData, wide: bool, packed: bool) -> ParseResult<LinuxLinkMap> {
+    /* struct MDRawLinkMap32 {
+        uint32_t addr;
+        MDRVA    name;
+        uint32_t ld;
+    } */
+
+    /* struct MDRawLinkMap64 {
+        uint64_t addr;
+        MDRVA    name;
+        uint64_t ld;
+    } */
+
+    let (raw, remain) = take(data, linux_link_map_size(wide, packed) as usize)?;
+
+    // Field ld follows the name RVA, aligned unless packed
+    let p = if wide { 8 } else { 4 };
+    let ld = if wide && !packed { 16 } else { p + 4 };
+
+    let link_map = LinuxLinkMap {
+        Addr: native_ptr(raw, 0, wide),
+        Name: LittleEndian::read_u32(&raw[p..p + 4]),
+        Ld: native_ptr(raw, ld, wide),
+
+        ModuleName: None,
+    };
+
+    Ok((link_map, remain))
+}
+
+pub fn parse_linux_dso_debug<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, LinuxDsoDebug> {
+    /* struct MDRawDebug32 {
+        uint32_t version;
+        MDRVA    map;       // array of MDRawLinkMap32
+        uint32_t dso_count;
+        uint32_t brk;
+        uint32_t ldbase;
+        uint32_t dynamic;
+    } */
+
+    /* struct MDRawDebug64 {
+        uint32_t version;
+        MDRVA    map;       // array of MDRawLinkMap64
+        uint32_t dso_count;
+        uint64_t brk;
+        uint64_t ldbase;
+        uint64_t dynamic;
+    } */
+
+    in_stream(StreamType::LinuxDsoDebugStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+
+        // The layout is identified by its size. Some writers pack the 64-bit
+        // structures and others align them.
+        let (wide, packed, base) = match loc.Length {
+            24 => (false, false, 12),
+            36 => (true, true, 12),
+            40 => (true, false, 16),
+            _ => return Err(ParseError::InvalidData("Unknown DSO debug structure size")),
+        };
+
+        let p = if wide { 8 } else { 4 };
+
+        let mut debug = LinuxDsoDebug {
+            Version: LittleEndian::read_u32(&raw[0..4]),
+            Map: LittleEndian::read_u32(&raw[4..8]),
+            DsoCount: LittleEndian::read_u32(&raw[8..12]),
+            Brk: native_ptr(raw, base, wide),
+            LdBase: native_ptr(raw, base + p, wide),
+            Dynamic: native_ptr(raw, base + 2 * p, wide),
+
+            LinkMaps: Vec::new(),
+        };
+
+        let SizeOfEntry = linux_link_map_size(wide, packed);
+
+        let (maps, _) = seek(data, debug.Map as u64)?;
+        let entries = list_iter(debug.DsoCount as u64, 0, SizeOfEntry, maps)?;
+        for (i, raw_entry) in entries.enumerate() {
+            let offset = debug.Map as u64 + i as u64 * SizeOfEntry;
+            let (mut entry, _) =
+                linux_link_map(raw_entry, wide, packed).map_err(|e| in_entry(i, offset, e))?;
+
+            if entry.Name != 0 {
+                let (name, _) =
+                    parse_string(data, entry.Name as u64).map_err(|e| in_entry(i, offset, e))?;
+                entry.ModuleName = Some(name);
+            }
+
+            debug.LinkMaps.push(entry);
+        }
+
+        Ok((debug, remain))
+    })
+}
+
+pub fn parse_breakpad_info<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, BreakpadInfo> {
+    /* struct MDRawBreakpadInfo {
+        uint32_t validity;
+        uint32_t dump_thread_id;
+        uint32_t requesting_thread_id;
+    } */
+
+    in_stream(StreamType::BreakpadInfoStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (raw, _) = take(raw, 12)?;
+
+        let Validity = LittleEndian::read_u32(&raw[0..4]);
+
+        let field = |flag: u32, offset: usize| {
+            if Validity & flag != 0 {
+                Some(LittleEndian::read_u32(&raw[offset..offset + 4]))
+            } else {
+                None
+            }
+        };
+
+        let info = BreakpadInfo {
+            Validity,
+            DumpThreadId: field(MD_BREAKPAD_INFO_VALID_DUMP_THREAD_ID, 4),
+            RequestingThreadId: field(MD_BREAKPAD_INFO_VALID_REQUESTING_THREAD_ID, 8),
+        };
+
+        Ok((info, remain))
+    })
+}
+
+pub fn parse_assertion_info<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, AssertionInfo> {
+    /* struct MDRawAssertionInfo {
+        uint16_t expression[128];
+        uint16_t function[128];
+        uint16_t file[128];
+        uint32_t line;
+        uint32_t type;
+    } */
+
+    in_stream(StreamType::AssertionInfoStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (raw, _) = take(raw, 776)?;
+
+        let info = AssertionInfo {
+            Expression: c_wstring(&raw[0..256]),
+            Function: c_wstring(&raw[256..512]),
+            File: c_wstring(&raw[512..768]),
+            Line: LittleEndian::read_u32(&raw[768..772]),
+            Type: LittleEndian::read_u32(&raw[772..776]),
+        };
+
+        Ok((info, remain))
+    })
+}
+
+fn utf8_string(data: ParseData, rva: u32) -> ParseResult<String> {
+    /* struct MinidumpUTF8String {
+        uint32_t length;
+        uint8_t  buffer[0];
+    } */
+
+    let (raw, _) = seek(data, rva as u64)?;
+    let (head, raw) = take(raw, 4)?;
+    let (raw, remain) = take(raw, LittleEndian::read_u32(head) as usize)?;
+
+    let string =
+        String::from_utf8(raw.to_vec()).map_err(|_| ParseError::InvalidData("Bad UTF-8 string"))?;
+
+    Ok((string, remain))
+}
+
+fn byte_array(data: ParseData, rva: u32) -> ParseResult<Vec<u8>> {
+    /* struct MinidumpByteArray {
+        uint32_t length;
+        uint8_t  data[0];
+    } */
+
+    let (raw, _) = seek(data, rva as u64)?;
+    let (head, raw) = take(raw, 4)?;
+    let (raw, remain) = take(raw, LittleEndian::read_u32(head) as usize)?;
+
+    Ok((raw.to_vec(), remain))
+}
+
+// Crashpad lists start with an entry count. An empty location means the list
+// is absent.
+fn crashpad_list<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+    SizeOfEntry: u64,
+) -> Result<slice::Chunks<'a, u8>, ParseError> {
+    if loc.Length == 0 {
+        return Ok([].chunks(1));
+    }
+
+    let (raw, _) = seek_stream(data, loc)?;
+    let (head, _) = take(raw, 4)?;
+    let count = LittleEndian::read_u32(head) as u64;
+
+    list_iter(count, 4, SizeOfEntry, raw)
+}
+
+fn string_dictionary(
+    data: ParseData,
+    loc: &LocationDescriptor,
+) -> Result<BTreeMap<String, String>, ParseError> {
+    /* struct MinidumpSimpleStringDictionaryEntry {
+        RVA key;
+        RVA value;
+    } */
+
+    let mut map = BTreeMap::new();
+    for (i, raw_entry) in crashpad_list(data, loc, 8)?.enumerate() {
+        let offset = loc.Offset + 4 + i as u64 * 8;
+        let entry = |rva| utf8_string(data, rva).map_err(|e| in_entry(i, offset, e));
+
+        let (key, _) = entry(LittleEndian::read_u32(&raw_entry[0..4]))?;
+        let (value, _) = entry(LittleEndian::read_u32(&raw_entry[4..8]))?;
+        map.insert(key, value);
+    }
+
+    Ok(map)
+}
+
+fn string_list(data: ParseData, loc: &LocationDescriptor) -> Result<Vec<String>, ParseError> {
+    /* struct MinidumpRVAList {
+        uint32_t count;
+        RVA      rvas[0];
+    } */
+
+    let mut vec = Vec::new();
+    for (i, raw_entry) in crashpad_list(data, loc, 4)?.enumerate() {
+        let offset = loc.Offset + 4 + i as u64 * 4;
+        let (string, _) = utf8_string(data, LittleEndian::read_u32(raw_entry))
+            .map_err(|e| in_entry(i, offset, e))?;
+        vec.push(string);
+    }
+
+    Ok(vec)
+}
+
+fn crashpad_annotation(data: ParseData, raw: ParseData) -> Result<CrashpadAnnotation, ParseError> {
+    /* struct MinidumpAnnotation {
+        RVA      name;
+        uint16_t type;
+        uint16_t reserved;
+        RVA      value;     // MinidumpByteArray
+    } */
+
+    let (Name, _) = utf8_string(data, LittleEndian::read_u32(&raw[0..4]))?;
+    let Type = LittleEndian::read_u16(&raw[4..6]);
+    let (Data, _) = byte_array(data, LittleEndian::read_u32(&raw[8..12]))?;
+
+    let Value = if Type == CRASHPAD_ANNOTATION_TYPE_STRING {
+        Some(String::from_utf8_lossy(&Data).into_owned())
+    } else {
+        None
+    };
+
+    Ok(CrashpadAnnotation {
+        Name,
+        Type,
+        Data,
+        Value,
+    })
+}
+
+fn module_crashpad_info(
+    data: ParseData,
+    raw_link: ParseData,
+) -> Result<ModuleCrashpadInfo, ParseError> {
+    /* struct MinidumpModuleCrashpadInfoLink {
+        uint32_t minidump_module_list_index;
+        MINIDUMP_LOCATION_DESCRIPTOR location;
+    } */
+
+    /* struct MinidumpModuleCrashpadInfo {
+        uint32_t version;
+        MINIDUMP_LOCATION_DESCRIPTOR list_annotations;
+        MINIDUMP_LOCATION_DESCRIPTOR simple_annotations;
+        MINIDUMP_LOCATION_DESCRIPTOR annotation_objects;
+    } */
+
+    let ModuleListIndex = LittleEndian::read_u32(&raw_link[0..4]);
+    let (loc, _) = location(&raw_link[4..12])?;
+
+    let (raw, _) = seek_stream(data, &loc)?;
+    let (raw, remain) = take(raw, 4)?;
+    let (list_annotations, remain) = location(remain)?;
+    let (simple_annotations, remain) = location(remain)?;
+    let (annotation_objects, _) = location(remain)?;
+
+    let mut AnnotationObjects = Vec::new();
+    for (i, raw_entry) in crashpad_list(data, &annotation_objects, 12)?.enumerate() {
+        let offset = annotation_objects.Offset + 4 + i as u64 * 12;
+        let annotation =
+            crashpad_annotation(data, raw_entry).map_err(|e| in_entry(i, offset, e))?;
+        AnnotationObjects.push(annotation);
+    }
+
+    Ok(ModuleCrashpadInfo {
+        ModuleListIndex,
+        Version: LittleEndian::read_u32(raw),
+        ListAnnotations: string_list(data, &list_annotations)?,
+        SimpleAnnotations: string_dictionary(data, &simple_annotations)?,
+        AnnotationObjects,
+
+        ModuleName: None,
+    })
+}
+
+pub fn parse_crashpad_info<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, CrashpadInfo> {
+    /* struct MinidumpCrashpadInfo {
+        uint32_t version;
+        UUID     report_id;
+        UUID     client_id;
+        MINIDUMP_LOCATION_DESCRIPTOR simple_annotations;
+        MINIDUMP_LOCATION_DESCRIPTOR module_list;
+    } */
+
+    in_stream(StreamType::CrashpadInfoStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, rest) = take(raw, 4)?;
+        let (ReportId, rest) = guid(rest)?;
+        let (ClientId, rest) = guid(rest)?;
+        let (simple_annotations, rest) = location(rest)?;
+        let (module_list, _) = location(rest)?;
+
+        // Links are MinidumpModuleCrashpadInfoLink entries
+        let mut ModuleList = Vec::new();
+        for (i, raw_entry) in crashpad_list(data, &module_list, 12)?.enumerate() {
+            let offset = module_list.Offset + 4 + i as u64 * 12;
+            let module = module_crashpad_info(data, raw_entry).map_err(|e| in_entry(i, offset, e))?;
+            ModuleList.push(module);
+        }
+
+        let info = CrashpadInfo {
+            Version: LittleEndian::read_u32(head),
+            ReportId,
+            ClientId,
+            SimpleAnnotations: string_dictionary(data, &simple_annotations)?,
+            ModuleList,
+        };
+
+        Ok((info, remain))
+    })
+}
+
+fn exception_record_32(data: ParseData) -> ParseResult<ExceptionRecord> {
+    /* struct EXCEPTION_RECORD32 {
+        DWORD       ExceptionCode;
+        DWORD       ExceptionFlags;
+        DWORD       ExceptionRecord;
+        DWORD       ExceptionAddress;
+        DWORD       NumberParameters;
+        DWORD       ExceptionInformation[EXCEPTION_MAXIMUM_PARAMETERS];
+    } */
+
+    let (raw, remain) = take(data, 80)?;
+
+    let ExceptionRecord = LittleEndian::read_u32(&raw[8..12]);
+    if ExceptionRecord != 0 {
+        return Err(ParseError::InvalidData("Unexpected exception chain"));
+    }
+
+    let NumberParameters = LittleEndian::read_u32(&raw[16..20]) as usize;
+    if NumberParameters > 15 {
+        return Err(ParseError::InvalidData("Invalid number of exception parameters"));
+    }
+
+    let (Information, _) = array_u32(&raw[20..], NumberParameters)?;
+
+    let rec = ExceptionRecord {
+        Code: LittleEndian::read_u32(&raw[0..4]),
+        Flags: LittleEndian::read_u32(&raw[4..8]),
+        Address: LittleEndian::read_u32(&raw[12..16]) as u64,
+        Information,
+    };
+
+    Ok((rec, remain))
+}
+
+fn exception_record_64(data: ParseData) -> ParseResult<ExceptionRecord> {
+    /* struct EXCEPTION_RECORD64 {
+        DWORD       ExceptionCode;
+        DWORD       ExceptionFlags;
+        DWORD64     ExceptionRecord;
+        DWORD64     ExceptionAddress;
+        DWORD       NumberParameters;
+        DWORD       __unusedAlignment;
+        DWORD64     ExceptionInformation[EXCEPTION_MAXIMUM_PARAMETERS];
+    } */
+
+    let (raw, remain) = take(data, 152)?;
+
+    let ExceptionRecord = LittleEndian::read_u64(&raw[8..16]);
+    if ExceptionRecord != 0 {
+        return Err(ParseError::InvalidData("Unexpected exception chain"));
+    }
+
+    let NumberParameters = LittleEndian::read_u32(&raw[24..28]) as usize;
+    if NumberParameters > 15 {
+        return Err(ParseError::InvalidData("Invalid number of exception parameters"));
     }
 
     let (Information, _) = array_u64(&raw[32..], NumberParameters)?;
@@ -728,7 +3402,7 @@ fn exception_record_64(data: ParseData) -> ParseResult<ExceptionRecord> {
         Code: LittleEndian::read_u32(&raw[0..4]),
         Flags: LittleEndian::read_u32(&raw[4..8]),
         Address: LittleEndian::read_u64(&raw[16..24]),
-        Information: Information,
+        Information,
     };
 
     Ok((rec, remain))
@@ -737,6 +3411,7 @@ fn exception_record_64(data: ParseData) -> ParseResult<ExceptionRecord> {
 pub fn parse_exception_stream<'a>(
     data: ParseData<'a>,
     loc: &LocationDescriptor,
+    info: ContextInfo,
 ) -> ParseResult<'a, ExceptionStream> {
     /* struct MINIDUMP_EXCEPTION_STREAM {
         ULONG32                         ThreadId;
@@ -745,36 +3420,49 @@ pub fn parse_exception_stream<'a>(
         MINIDUMP_LOCATION_DESCRIPTOR    ThreadContext;
     } */
 
-    let (seek_raw, seek_remain) = seek_stream(data, loc)?;
-
-    let SizeOfHeader32 = 104;
-    let SizeOfHeader64 = 168;
-    if SizeOfHeader32 != loc.Length && SizeOfHeader64 != loc.Length {
-        return Err("Unexpected Stream size");
-    }
+    in_stream(StreamType::ExceptionStream, loc, || {
+        let (seek_raw, seek_remain) = seek_stream(data, loc)?;
+
+        let SizeOfHeader32 = 104;
+        let SizeOfHeader64 = 168;
+        if SizeOfHeader32 != loc.Length && SizeOfHeader64 != loc.Length {
+            // Report the size of the layout nearest to the stream
+            let expected = if loc.Length < (SizeOfHeader32 + SizeOfHeader64) / 2 {
+                SizeOfHeader32
+            } else {
+                SizeOfHeader64
+            };
+            return Err(ParseError::UnexpectedStreamSize {
+                expected,
+                actual: loc.Length,
+            });
+        }
 
-    // Use length of stream to guess format
-    let exception_record_fn = if SizeOfHeader32 == loc.Length {
-        exception_record_32
-    } else {
-        exception_record_64
-    };
+        // Use length of stream to guess format
+        let exception_record_fn = if SizeOfHeader32 == loc.Length {
+            exception_record_32
+        } else {
+            exception_record_64
+        };
 
-    let (raw, remain) = take(seek_raw, 8)?;
-    let (exception_record, remain) = exception_record_fn(remain)?;
-    let (context_loc, _) = location(remain)?;
+        let (raw, remain) = take(seek_raw, 8)?;
+        let (exception_record, remain) = exception_record_fn(remain)?;
+        let (context_loc, _) = location(remain)?;
 
-    let (context, _) = thread_context(data, &context_loc)?;
+        let (context, context_error) = checked_thread_context(data, &context_loc, info);
 
-    let exception_stream = ExceptionStream {
-        ThreadId: LittleEndian::read_u32(&raw[0..4]),
-        Exception: exception_record,
-        ThreadContext: context_loc,
+        let exception_stream = ExceptionStream {
+            ThreadId: LittleEndian::read_u32(&raw[0..4]),
+            Exception: exception_record,
+            ThreadContext: context_loc,
 
-        Context: context,
-    };
+            Context: context,
+            ContextError: context_error,
+            Wow64Context: None,
+        };
 
-    Ok((exception_stream, seek_remain))
+        Ok((exception_stream, seek_remain))
+    })
 }
 
 pub fn parse_system_info<'a>(
@@ -797,38 +3485,1135 @@ pub fn parse_system_info<'a>(
         ULONG32 ProcessorFeatures[6];
     } */
 
-    let (seek_raw, seek_remain) = seek_stream(data, loc)?;
+    in_stream(StreamType::SystemInfoStream, loc, || {
+        let (seek_raw, seek_remain) = seek_stream(data, loc)?;
 
-    let SizeOfHeader = 56;
-    if SizeOfHeader != loc.Length {
-        return Err("Unexpected Stream size");
-    }
+        let SizeOfHeader = 56;
+        if SizeOfHeader != loc.Length {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfHeader,
+                actual: loc.Length,
+            });
+        }
+
+        let (raw, remain) = take(seek_raw, 32)?;
+        let (features, _) = array_u32(remain, 6)?;
+
+        let proc_revision = LittleEndian::read_u16(&raw[4..6]);
+
+        let mut system_info = SystemInfo {
+            ProcessorArchitecture: LittleEndian::read_u16(&raw[0..2]),
+            ProcessorFamily: LittleEndian::read_u16(&raw[2..4]),
+            ProcessorModel: (proc_revision >> 8) as u8,
+            ProcessorStepping: (proc_revision & 0xFF) as u8,
+            NumberOfProcessors: raw[6],
+            MajorVersion: LittleEndian::read_u32(&raw[8..12]),
+            MinorVersion: LittleEndian::read_u32(&raw[12..16]),
+            BuildNumber: LittleEndian::read_u32(&raw[16..20]),
+            CSDVersionRva: LittleEndian::read_u32(&raw[24..28]),
+            ProcessorFeatures: features,
+
+            ServicePack: None,
+        };
+
+        // Look up service pack string
+        if system_info.CSDVersionRva > 0 {
+            let (name, _) = parse_string(data, system_info.CSDVersionRva as u64)?;
+            system_info.ServicePack = Some(name);
+        }
+
+        Ok((system_info, seek_remain))
+    })
+}
+
+fn system_basic_information(data: ParseData) -> ParseResult<SystemBasicInformation> {
+    /* struct MINIDUMP_SYSTEM_BASIC_INFORMATION {
+        ULONG   TimerResolution;
+        ULONG   PageSize;
+        ULONG   NumberOfPhysicalPages;
+        ULONG   LowestPhysicalPageNumber;
+        ULONG   HighestPhysicalPageNumber;
+        ULONG   AllocationGranularity;
+        ULONG64 MinimumUserModeAddress;
+        ULONG64 MaximumUserModeAddress;
+        ULONG64 ActiveProcessorsAffinityMask;
+        ULONG   NumberOfProcessors;
+    } */
+
+    let (raw, remain) = take(data, 52)?;
+
+    let info = SystemBasicInformation {
+        TimerResolution: LittleEndian::read_u32(&raw[0..4]),
+        PageSize: LittleEndian::read_u32(&raw[4..8]),
+        NumberOfPhysicalPages: LittleEndian::read_u32(&raw[8..12]),
+        LowestPhysicalPageNumber: LittleEndian::read_u32(&raw[12..16]),
+        HighestPhysicalPageNumber: LittleEndian::read_u32(&raw[16..20]),
+        AllocationGranularity: LittleEndian::read_u32(&raw[20..24]),
+        MinimumUserModeAddress: LittleEndian::read_u64(&raw[24..32]),
+        MaximumUserModeAddress: LittleEndian::read_u64(&raw[32..40]),
+        ActiveProcessorsAffinityMask: LittleEndian::read_u64(&raw[40..48]),
+        NumberOfProcessors: LittleEndian::read_u32(&raw[48..52]),
+    };
+
+    Ok((info, remain))
+}
+
+fn system_file_cache_information(data: ParseData) -> ParseResult<SystemFileCacheInformation> {
+    /* struct MINIDUMP_SYSTEM_FILECACHE_INFORMATION {
+        ULONG64 CurrentSize;
+        ULONG64 PeakSize;
+        ULONG   PageFaultCount;
+        ULONG64 MinimumWorkingSet;
+        ULONG64 MaximumWorkingSet;
+        ULONG64 CurrentSizeIncludingTransitionInPages;
+        ULONG64 PeakSizeIncludingTransitionInPages;
+        ULONG   TransitionRePurposeCount;
+        ULONG   Flags;
+    } */
+
+    let (raw, remain) = take(data, 60)?;
+
+    let info = SystemFileCacheInformation {
+        CurrentSize: LittleEndian::read_u64(&raw[0..8]),
+        PeakSize: LittleEndian::read_u64(&raw[8..16]),
+        PageFaultCount: LittleEndian::read_u32(&raw[16..20]),
+        MinimumWorkingSet: LittleEndian::read_u64(&raw[20..28]),
+        MaximumWorkingSet: LittleEndian::read_u64(&raw[28..36]),
+        CurrentSizeIncludingTransitionInPages: LittleEndian::read_u64(&raw[36..44]),
+        PeakSizeIncludingTransitionInPages: LittleEndian::read_u64(&raw[44..52]),
+        TransitionRePurposeCount: LittleEndian::read_u32(&raw[52..56]),
+        Flags: LittleEndian::read_u32(&raw[56..60]),
+    };
+
+    Ok((info, remain))
+}
+
+fn system_basic_performance_information(
+    data: ParseData,
+) -> ParseResult<SystemBasicPerformanceInformation> {
+    /* struct MINIDUMP_SYSTEM_BASIC_PERFORMANCE_INFORMATION {
+        ULONG64 AvailablePages;
+        ULONG64 CommittedPages;
+        ULONG64 CommitLimit;
+        ULONG64 PeakCommitment;
+    } */
+
+    let (raw, remain) = take(data, 32)?;
+
+    let info = SystemBasicPerformanceInformation {
+        AvailablePages: LittleEndian::read_u64(&raw[0..8]),
+        CommittedPages: LittleEndian::read_u64(&raw[8..16]),
+        CommitLimit: LittleEndian::read_u64(&raw[16..24]),
+        PeakCommitment: LittleEndian::read_u64(&raw[24..32]),
+    };
+
+    Ok((info, remain))
+}
+
+fn system_performance_information(data: ParseData) -> ParseResult<SystemPerformanceInformation> {
+    /* struct MINIDUMP_SYSTEM_PERFORMANCE_INFORMATION {
+        ULONG64 IdleProcessTime;
+        ULONG64 IoReadTransferCount;
+        ULONG64 IoWriteTransferCount;
+        ULONG64 IoOtherTransferCount;
+        ULONG   IoReadOperationCount;
+        ULONG   IoWriteOperationCount;
+        ULONG   IoOtherOperationCount;
+        ULONG   AvailablePages;
+        ULONG   CommittedPages;
+        ULONG   CommitLimit;
+        ULONG   PeakCommitment;
+        ULONG   PageFaultCount;
+        ...
+        ULONG   PagedPoolPages;             // +0x070
+        ULONG   NonPagedPoolPages;
+        ...
+        ULONG   ContextSwitches;            // +0x128
+        ULONG   FirstLevelTbFills;
+        ULONG   SecondLevelTbFills;
+        ULONG   SystemCalls;
+        ULONG64 CcTotalDirtyPages;
+        ULONG64 CcDirtyPageThreshold;
+        LONG64  ResidentAvailablePages;
+        ULONG64 SharedCommittedPages;
+    } */
+
+    let (raw, remain) = take(data, 344)?;
+
+    let info = SystemPerformanceInformation {
+        IdleProcessTime: LittleEndian::read_u64(&raw[0..8]),
+        IoReadTransferCount: LittleEndian::read_u64(&raw[8..16]),
+        IoWriteTransferCount: LittleEndian::read_u64(&raw[16..24]),
+        IoOtherTransferCount: LittleEndian::read_u64(&raw[24..32]),
+        AvailablePages: LittleEndian::read_u32(&raw[44..48]),
+        CommittedPages: LittleEndian::read_u32(&raw[48..52]),
+        CommitLimit: LittleEndian::read_u32(&raw[52..56]),
+        PeakCommitment: LittleEndian::read_u32(&raw[56..60]),
+        PageFaultCount: LittleEndian::read_u32(&raw[60..64]),
+        PagedPoolPages: LittleEndian::read_u32(&raw[112..116]),
+        NonPagedPoolPages: LittleEndian::read_u32(&raw[116..120]),
+        ContextSwitches: LittleEndian::read_u32(&raw[296..300]),
+        SystemCalls: LittleEndian::read_u32(&raw[308..312]),
+        ResidentAvailablePages: LittleEndian::read_i64(&raw[328..336]),
+        SharedCommittedPages: LittleEndian::read_u64(&raw[336..344]),
+    };
+
+    Ok((info, remain))
+}
+
+pub fn parse_system_memory_info<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, SystemMemoryInfo> {
+    /* struct MINIDUMP_SYSTEM_MEMORY_INFO_1 {
+        USHORT Revision;
+        USHORT Flags;
+        MINIDUMP_SYSTEM_BASIC_INFORMATION               BasicInfo;
+        MINIDUMP_SYSTEM_FILECACHE_INFORMATION           FileCacheInfo;
+        MINIDUMP_SYSTEM_BASIC_PERFORMANCE_INFORMATION   BasicPerfInfo;
+        MINIDUMP_SYSTEM_PERFORMANCE_INFORMATION         PerfInfo;
+    } */
+
+    in_stream(StreamType::SystemMemoryInfoStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+
+        // Structures are packed to 4 bytes
+        let SizeOfInfo = 492;
+        if loc.Length < SizeOfInfo {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfInfo,
+                actual: loc.Length,
+            });
+        }
+
+        let (head, rest) = take(raw, 4)?;
+        let (BasicInfo, rest) = system_basic_information(rest)?;
+        let (FileCacheInfo, rest) = system_file_cache_information(rest)?;
+        let (BasicPerfInfo, rest) = system_basic_performance_information(rest)?;
+        let (PerfInfo, _) = system_performance_information(rest)?;
+
+        let info = SystemMemoryInfo {
+            Revision: LittleEndian::read_u16(&head[0..2]),
+            Flags: LittleEndian::read_u16(&head[2..4]),
+            BasicInfo,
+            FileCacheInfo,
+            BasicPerfInfo,
+            PerfInfo,
+        };
+
+        Ok((info, remain))
+    })
+}
+
+pub fn parse_process_vm_counters<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, ProcessVmCounters> {
+    /* struct MINIDUMP_PROCESS_VM_COUNTERS_2 {
+        USHORT  Revision;
+        USHORT  Flags;
+        ULONG   PageFaultCount;
+        ULONG64 PeakWorkingSetSize;
+        ULONG64 WorkingSetSize;
+        ULONG64 QuotaPeakPagedPoolUsage;
+        ULONG64 QuotaPagedPoolUsage;
+        ULONG64 QuotaPeakNonPagedPoolUsage;
+        ULONG64 QuotaNonPagedPoolUsage;
+        ULONG64 PagefileUsage;
+        ULONG64 PeakPagefileUsage;
+        ULONG64 PeakVirtualSize;            // VIRTUALSIZE
+        ULONG64 VirtualSize;                // VIRTUALSIZE
+        ULONG64 PrivateUsage;               // EX
+        ULONG64 PrivateWorkingSetSize;      // EX2
+        ULONG64 SharedCommitUsage;          // EX2
+        ULONG64 JobSharedCommitUsage;       // JOB
+        ULONG64 JobPrivateCommitUsage;      // JOB
+        ULONG64 JobPeakPrivateCommitUsage;  // JOB
+        ULONG64 JobPrivateCommitLimit;      // JOB
+        ULONG64 JobTotalCommitLimit;        // JOB
+    } */
+
+    /* struct MINIDUMP_PROCESS_VM_COUNTERS_1 {
+        USHORT  Revision;
+        ULONG   PageFaultCount;
+        ...
+        ULONG64 PeakPagefileUsage;
+        ULONG64 PrivateUsage;
+    } */
+
+    in_stream(StreamType::ProcessVmCountersStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 4)?;
+
+        // Revision 1 has no flags and always holds the basic and EX counters
+        let Revision = LittleEndian::read_u16(&head[0..2]);
+        let (SizeOfInfo, Flags) = match Revision {
+            1 => (80, MINIDUMP_PROCESS_VM_COUNTERS | MINIDUMP_PROCESS_VM_COUNTERS_EX),
+            2 => (152, LittleEndian::read_u16(&head[2..4])),
+            _ => return Err(ParseError::InvalidData("Unknown VM counters revision")),
+        };
+
+        if loc.Length < SizeOfInfo {
+            return Err(ParseError::UnexpectedStreamSize {
+                expected: SizeOfInfo,
+                actual: loc.Length,
+            });
+        }
+
+        let (raw, _) = take(raw, SizeOfInfo as usize)?;
+
+        let field = |flag: u16, offset: usize| {
+            if Flags & flag != 0 {
+                Some(LittleEndian::read_u64(&raw[offset..offset + 8]))
+            } else {
+                None
+            }
+        };
+
+        // PrivateUsage directly follows the basic counters in revision 1
+        let PrivateUsage = match Revision {
+            1 => field(MINIDUMP_PROCESS_VM_COUNTERS_EX, 72),
+            _ => field(MINIDUMP_PROCESS_VM_COUNTERS_EX, 88),
+        };
+
+        let PageFaultCount = if Flags & MINIDUMP_PROCESS_VM_COUNTERS != 0 {
+            Some(LittleEndian::read_u32(&raw[4..8]))
+        } else {
+            None
+        };
+
+        let counters = ProcessVmCounters {
+            Revision,
+            Flags,
+            PageFaultCount,
+            PeakWorkingSetSize: field(MINIDUMP_PROCESS_VM_COUNTERS, 8),
+            WorkingSetSize: field(MINIDUMP_PROCESS_VM_COUNTERS, 16),
+            QuotaPeakPagedPoolUsage: field(MINIDUMP_PROCESS_VM_COUNTERS, 24),
+            QuotaPagedPoolUsage: field(MINIDUMP_PROCESS_VM_COUNTERS, 32),
+            QuotaPeakNonPagedPoolUsage: field(MINIDUMP_PROCESS_VM_COUNTERS, 40),
+            QuotaNonPagedPoolUsage: field(MINIDUMP_PROCESS_VM_COUNTERS, 48),
+            PagefileUsage: field(MINIDUMP_PROCESS_VM_COUNTERS, 56),
+            PeakPagefileUsage: field(MINIDUMP_PROCESS_VM_COUNTERS, 64),
+            PeakVirtualSize: field(MINIDUMP_PROCESS_VM_COUNTERS_VIRTUALSIZE, 72),
+            VirtualSize: field(MINIDUMP_PROCESS_VM_COUNTERS_VIRTUALSIZE, 80),
+            PrivateUsage,
+            PrivateWorkingSetSize: field(MINIDUMP_PROCESS_VM_COUNTERS_EX2, 96),
+            SharedCommitUsage: field(MINIDUMP_PROCESS_VM_COUNTERS_EX2, 104),
+            JobSharedCommitUsage: field(MINIDUMP_PROCESS_VM_COUNTERS_JOB, 112),
+            JobPrivateCommitUsage: field(MINIDUMP_PROCESS_VM_COUNTERS_JOB, 120),
+            JobPeakPrivateCommitUsage: field(MINIDUMP_PROCESS_VM_COUNTERS_JOB, 128),
+            JobPrivateCommitLimit: field(MINIDUMP_PROCESS_VM_COUNTERS_JOB, 136),
+            JobTotalCommitLimit: field(MINIDUMP_PROCESS_VM_COUNTERS_JOB, 144),
+        };
+
+        Ok((counters, remain))
+    })
+}
+
+fn system_time(data: ParseData) -> ParseResult<SystemTime> {
+    /* struct SYSTEMTIME {
+        WORD wYear;
+        WORD wMonth;
+        WORD wDayOfWeek;
+        WORD wDay;
+        WORD wHour;
+        WORD wMinute;
+        WORD wSecond;
+        WORD wMilliseconds;
+    } */
+
+    let (raw, remain) = take(data, 16)?;
+
+    let time = SystemTime {
+        Year: LittleEndian::read_u16(&raw[0..2]),
+        Month: LittleEndian::read_u16(&raw[2..4]),
+        DayOfWeek: LittleEndian::read_u16(&raw[4..6]),
+        Day: LittleEndian::read_u16(&raw[6..8]),
+        Hour: LittleEndian::read_u16(&raw[8..10]),
+        Minute: LittleEndian::read_u16(&raw[10..12]),
+        Second: LittleEndian::read_u16(&raw[12..14]),
+        Milliseconds: LittleEndian::read_u16(&raw[14..16]),
+    };
+
+    Ok((time, remain))
+}
+
+fn time_zone_information(data: ParseData) -> ParseResult<TimeZoneInformation> {
+    /* struct TIME_ZONE_INFORMATION {
+        LONG        Bias;
+        WCHAR       StandardName[32];
+        SYSTEMTIME  StandardDate;
+        LONG        StandardBias;
+        WCHAR       DaylightName[32];
+        SYSTEMTIME  DaylightDate;
+        LONG        DaylightBias;
+    } */
+
+    let (raw, remain) = take(data, 172)?;
 
-    let (raw, remain) = take(seek_raw, 32)?;
-    let (features, _) = array_u32(remain, 6)?;
+    let (StandardDate, _) = system_time(&raw[68..84])?;
+    let (DaylightDate, _) = system_time(&raw[152..168])?;
 
-    let proc_revision = LittleEndian::read_u16(&raw[4..6]);
+    let tz = TimeZoneInformation {
+        Bias: LittleEndian::read_i32(&raw[0..4]),
+        StandardName: c_wstring(&raw[4..68]),
+        StandardDate,
+        StandardBias: LittleEndian::read_i32(&raw[84..88]),
+        DaylightName: c_wstring(&raw[88..152]),
+        DaylightDate,
+        DaylightBias: LittleEndian::read_i32(&raw[168..172]),
+    };
+
+    Ok((tz, remain))
+}
+
+fn xstate_config(data: ParseData) -> ParseResult<XStateConfigFeatureMscInfo> {
+    /* struct XSTATE_CONFIG_FEATURE_MSC_INFO {
+        ULONG32         SizeOfInfo;
+        ULONG32         ContextSize;
+        ULONG64         EnabledFeatures;
+        XSTATE_FEATURE  Features[MAXIMUM_XSTATE_FEATURES];
+    }
 
-    let mut system_info = SystemInfo {
-        ProcessorArchitecture: LittleEndian::read_u16(&raw[0..2]),
-        ProcessorFamily: LittleEndian::read_u16(&raw[2..4]),
-        ProcessorModel: (proc_revision >> 8) as u8,
-        ProcessorStepping: (proc_revision & 0xFF) as u8,
-        NumberOfProcessors: raw[6],
-        MajorVersion: LittleEndian::read_u32(&raw[8..12]),
-        MinorVersion: LittleEndian::read_u32(&raw[12..16]),
-        BuildNumber: LittleEndian::read_u32(&raw[16..20]),
-        CSDVersionRva: LittleEndian::read_u32(&raw[24..28]),
-        ProcessorFeatures: features,
+    struct XSTATE_FEATURE {
+        ULONG32 Offset;
+        ULONG32 Size;
+    } */
 
-        ServicePack: None,
+    let (raw, remain) = take(data, 528)?;
+    let (fields, _) = array_u32(&raw[16..], 128)?;
+
+    let Features = fields
+        .chunks(2)
+        .map(|f| XStateFeature {
+            Offset: f[0],
+            Size: f[1],
+        })
+        .collect();
+
+    let config = XStateConfigFeatureMscInfo {
+        SizeOfInfo: LittleEndian::read_u32(&raw[0..4]),
+        ContextSize: LittleEndian::read_u32(&raw[4..8]),
+        EnabledFeatures: LittleEndian::read_u64(&raw[8..16]),
+        Features,
     };
 
-    // Look up service pack string
-    if system_info.CSDVersionRva > 0 {
-        let (name, _) = parse_string(data, system_info.CSDVersionRva)?;
-        system_info.ServicePack = Some(name);
+    Ok((config, remain))
+}
+
+pub fn parse_misc_info<'a>(
+    data: ParseData<'a>,
+    loc: &LocationDescriptor,
+) -> ParseResult<'a, MiscInfo> {
+    /* struct MINIDUMP_MISC_INFO {
+        ULONG32 SizeOfInfo;
+        ULONG32 Flags1;
+        ULONG32 ProcessId;
+        ULONG32 ProcessCreateTime;
+        ULONG32 ProcessUserTime;
+        ULONG32 ProcessKernelTime;
+
+        // +0024: MINIDUMP_MISC_INFO_2
+        ULONG32 ProcessorMaxMhz;
+        ULONG32 ProcessorCurrentMhz;
+        ULONG32 ProcessorMhzLimit;
+        ULONG32 ProcessorMaxIdleState;
+        ULONG32 ProcessorCurrentIdleState;
+
+        // +0044: MINIDUMP_MISC_INFO_3
+        ULONG32 ProcessIntegrityLevel;
+        ULONG32 ProcessExecuteFlags;
+        ULONG32 ProtectedProcess;
+        ULONG32 TimeZoneId;
+        TIME_ZONE_INFORMATION TimeZone;
+
+        // +0232: MINIDUMP_MISC_INFO_4
+        WCHAR   BuildString[MAX_PATH];
+        WCHAR   DbgBldStr[40];
+
+        // +0832: MINIDUMP_MISC_INFO_5
+        XSTATE_CONFIG_FEATURE_MSC_INFO XStateData;
+        ULONG32 ProcessCookie;
+    } */
+
+    in_stream(StreamType::MiscInfoStream, loc, || {
+        let (raw, remain) = seek_stream(data, loc)?;
+        let (head, _) = take(raw, 24)?;
+
+        // The structure version is determined by its size. Fields beyond the
+        // end of the stream are treated as absent.
+        let SizeOfInfo = LittleEndian::read_u32(&head[0..4]);
+        let Flags1 = LittleEndian::read_u32(&head[4..8]);
+        let raw = &raw[..(SizeOfInfo as usize).min(raw.len())];
+
+        let field = |flag: u32, offset: usize| {
+            if Flags1 & flag != 0 && raw.len() >= offset + 4 {
+                Some(LittleEndian::read_u32(&raw[offset..offset + 4]))
+            } else {
+                None
+            }
+        };
+
+        let mut misc_info = MiscInfo {
+            SizeOfInfo,
+            Flags1,
+            ProcessId: field(MINIDUMP_MISC1_PROCESS_ID, 8),
+            ProcessCreateTime: field(MINIDUMP_MISC1_PROCESS_TIMES, 12),
+            ProcessUserTime: field(MINIDUMP_MISC1_PROCESS_TIMES, 16),
+            ProcessKernelTime: field(MINIDUMP_MISC1_PROCESS_TIMES, 20),
+            ProcessorMaxMhz: field(MINIDUMP_MISC1_PROCESSOR_POWER_INFO, 24),
+            ProcessorCurrentMhz: field(MINIDUMP_MISC1_PROCESSOR_POWER_INFO, 28),
+            ProcessorMhzLimit: field(MINIDUMP_MISC1_PROCESSOR_POWER_INFO, 32),
+            ProcessorMaxIdleState: field(MINIDUMP_MISC1_PROCESSOR_POWER_INFO, 36),
+            ProcessorCurrentIdleState: field(MINIDUMP_MISC1_PROCESSOR_POWER_INFO, 40),
+            ProcessIntegrityLevel: field(MINIDUMP_MISC3_PROCESS_INTEGRITY, 44),
+            ProcessExecuteFlags: field(MINIDUMP_MISC3_PROCESS_EXECUTE_FLAGS, 48),
+            ProtectedProcess: field(MINIDUMP_MISC3_PROTECTED_PROCESS, 52),
+            TimeZoneId: field(MINIDUMP_MISC3_TIMEZONE, 56),
+            TimeZone: None,
+            BuildString: None,
+            DbgBldStr: None,
+            XStateData: None,
+            ProcessCookie: field(MINIDUMP_MISC5_PROCESS_COOKIE, 1360),
+        };
+
+        if Flags1 & MINIDUMP_MISC3_TIMEZONE != 0 && raw.len() >= 232 {
+            let (tz, _) = time_zone_information(&raw[60..232])?;
+            misc_info.TimeZone = Some(tz);
+        }
+
+        if Flags1 & MINIDUMP_MISC4_BUILDSTRING != 0 && raw.len() >= 832 {
+            misc_info.BuildString = Some(c_wstring(&raw[232..752]));
+            misc_info.DbgBldStr = Some(c_wstring(&raw[752..832]));
+        }
+
+        // XSTATE data has no validity flag, but is zero-filled when not present
+        if raw.len() >= 1360 {
+            let (xstate, _) = xstate_config(&raw[832..1360])?;
+            if xstate.SizeOfInfo != 0 {
+                misc_info.XStateData = Some(xstate);
+            }
+        }
+
+        Ok((misc_info, remain))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sid_bytes(authority: u8, sub_authorities: &[u32]) -> Vec<u8> {
+        let mut raw = vec![1, sub_authorities.len() as u8, 0, 0, 0, 0, 0, authority];
+        for sub in sub_authorities {
+            raw.extend_from_slice(&sub.to_le_bytes());
+        }
+        raw
+    }
+
+    // SID_AND_ATTRIBUTES entries with pointers into the writer's buffer
+    fn entry_bytes(wide: bool, attributes: u32) -> Vec<u8> {
+        let mut raw = vec![0xAA; if wide { 8 } else { 4 }];
+        raw.extend_from_slice(&attributes.to_le_bytes());
+        if wide {
+            raw.extend_from_slice(&[0; 4]);
+        }
+        raw
+    }
+
+    fn token_data(wide: bool) -> Vec<u8> {
+        let groups = [
+            (sid_bytes(1, &[0]), 7),
+            (sid_bytes(5, &[32, 545]), 7),
+            (sid_bytes(16, &[0x1000]), SE_GROUP_INTEGRITY),
+        ];
+
+        // TOKEN_USER
+        let mut raw = entry_bytes(wide, 0);
+        raw.extend(sid_bytes(5, &[21, 1111, 2222, 3333, 1001]));
+
+        // TOKEN_GROUPS
+        raw.extend_from_slice(&(groups.len() as u32).to_le_bytes());
+        if wide {
+            raw.extend_from_slice(&[0; 4]);
+        }
+        for (_, attributes) in groups.iter() {
+            raw.extend(entry_bytes(wide, *attributes));
+        }
+        for (sid, _) in groups.iter() {
+            raw.extend_from_slice(sid);
+        }
+
+        // TOKEN_PRIVILEGES
+        raw.extend_from_slice(&2u32.to_le_bytes());
+        for &(luid, attributes) in [(23u64, SE_PRIVILEGE_ENABLED), (99, 0)].iter() {
+            raw.extend_from_slice(&luid.to_le_bytes());
+            raw.extend_from_slice(&attributes.to_le_bytes());
+        }
+
+        raw
+    }
+
+    fn token_entry(data: &[u8], wide: bool) -> TokenInfo {
+        let mut raw = Vec::new();
+        raw.extend_from_slice(&(16 + data.len() as u32).to_le_bytes());
+        raw.extend_from_slice(&7u32.to_le_bytes());
+        raw.extend_from_slice(&0x1234u64.to_le_bytes());
+        raw.extend_from_slice(data);
+
+        token_info(&raw, 16, wide).unwrap().0
+    }
+
+    #[test]
+    fn token_layout() {
+        for &wide in [false, true].iter() {
+            let token = token_entry(&token_data(wide), wide);
+
+            assert_eq!(token.TokenId, 7);
+            assert_eq!(token.TokenHandle, 0x1234);
+            assert_eq!(token.User.as_deref(), Some("S-1-5-21-1111-2222-3333-1001"));
+            assert_eq!(token.Groups, ["S-1-1-0", "S-1-5-32-545"]);
+            assert_eq!(token.IntegrityLevel, Some(0x1000));
+            assert_eq!(token.IntegrityLevelName, Some("Low"));
+
+            assert_eq!(token.Privileges.len(), 2);
+            assert_eq!(token.Privileges[0].Name, Some("SeChangeNotifyPrivilege"));
+            assert!(token.Privileges[0].Enabled);
+            assert_eq!(token.Privileges[1].Name, None);
+            assert!(!token.Privileges[1].Enabled);
+        }
+    }
+
+    #[test]
+    fn token_layout_mismatch() {
+        // A 32-bit layout read as 64-bit, and truncated data
+        let data = token_data(false);
+        for token in [token_entry(&data, true), token_entry(&data[..40], false)].iter() {
+            assert_eq!(token.User, None);
+            assert!(token.Groups.is_empty());
+            assert!(token.Privileges.is_empty());
+            assert_eq!(token.IntegrityLevel, None);
+            assert_eq!(token.Data.len(), token.TokenSize as usize - 16);
+        }
+    }
+
+    fn put_u32(raw: &mut [u8], offset: usize, value: u32) {
+        LittleEndian::write_u32(&mut raw[offset..offset + 4], value);
+    }
+
+    fn put_u64(raw: &mut [u8], offset: usize, value: u64) {
+        LittleEndian::write_u64(&mut raw[offset..offset + 8], value);
+    }
+
+    // Location of a stream which is the whole of the data
+    fn whole(raw: &[u8]) -> LocationDescriptor {
+        LocationDescriptor {
+            Offset: 0,
+            Length: raw.len() as u64,
+        }
+    }
+
+    #[test]
+    fn misc_info_version_1() {
+        let mut raw = vec![0; 24];
+        put_u32(&mut raw, 0, 24);
+        put_u32(&mut raw, 4, MINIDUMP_MISC1_PROCESS_ID);
+        put_u32(&mut raw, 8, 4242);
+        put_u32(&mut raw, 12, 1000);
+
+        let (info, _) = parse_misc_info(&raw, &whole(&raw)).unwrap();
+        assert_eq!(info.ProcessId, Some(4242));
+        assert_eq!(info.ProcessCreateTime, None);
+        assert_eq!(info.ProcessorMaxMhz, None);
+        assert!(info.TimeZone.is_none());
+        assert!(info.XStateData.is_none());
+        assert_eq!(info.ProcessCookie, None);
+    }
+
+    #[test]
+    fn misc_info_version_5() {
+        let mut raw = vec![0; 1364];
+        put_u32(&mut raw, 0, 1364);
+        put_u32(
+            &mut raw,
+            4,
+            MINIDUMP_MISC1_PROCESS_TIMES | MINIDUMP_MISC3_TIMEZONE | MINIDUMP_MISC4_BUILDSTRING,
+        );
+        put_u32(&mut raw, 12, 1000);
+        put_u32(&mut raw, 24, 3000);
+        put_u32(&mut raw, 60, (-60i32) as u32);
+        raw[232] = b'1';
+        raw[1360] = 0x99;
+
+        // XSTATE_CONFIG_FEATURE_MSC_INFO with AVX enabled
+        put_u32(&mut raw, 832, 528);
+        put_u32(&mut raw, 836, 2048);
+        put_u64(&mut raw, 840, 0x7);
+        put_u32(&mut raw, 848 + 8 * XSTATE_AVX, 576);
+        put_u32(&mut raw, 852 + 8 * XSTATE_AVX, 256);
+
+        let (info, _) = parse_misc_info(&raw, &whole(&raw)).unwrap();
+        assert_eq!(info.ProcessId, None);
+        assert_eq!(info.ProcessCreateTime, Some(1000));
+        assert_eq!(info.ProcessorMaxMhz, None);
+        assert_eq!(info.ProcessIntegrityLevel, None);
+        assert_eq!(info.TimeZone.map(|tz| tz.Bias), Some(-60));
+        assert_eq!(info.BuildString.as_deref(), Some("1"));
+        assert_eq!(info.ProcessCookie, None);
+
+        let xstate = info.XStateData.unwrap();
+        assert_eq!(xstate.ContextSize, 2048);
+        assert_eq!(xstate.EnabledFeatures, 0x7);
+        assert_eq!(xstate.Features[XSTATE_AVX].Offset, 576);
+        assert_eq!(xstate.Features[XSTATE_AVX].Size, 256);
+    }
+
+    #[test]
+    fn linux_maps_lines() {
+        let text = concat!(
+            "00400000-00452000 r-xp 00000000 08:02 173521      /usr/bin/app\n",
+            "7f0000000000-7f0000001000 ---p 00000000 00:00 0 \n",
+            "\n",
+            "7f1000010000-7f1000011000 rw-s 00001000 00:05 7    /dev/shm/buf (deleted)\n",
+            "7ffc00000000-7ffc00021000 rw-p 00000000 00:00 0    [stack]\n",
+        );
+
+        let (maps, _) = parse_linux_maps(text.as_bytes(), &whole(text.as_bytes())).unwrap();
+        assert_eq!(maps.len(), 4);
+
+        assert_eq!((maps[0].Start, maps[0].End), (0x400000, 0x452000));
+        assert_eq!(maps[0].Permissions, "r-xp");
+        assert_eq!(maps[0].Device, "08:02");
+        assert_eq!(maps[0].Inode, 173521);
+        assert_eq!(maps[0].Path.as_deref(), Some("/usr/bin/app"));
+
+        assert_eq!(maps[1].Start, 0x7f0000000000);
+        assert_eq!(maps[1].Path, None);
+
+        assert_eq!(maps[2].Offset, 0x1000);
+        assert_eq!(maps[2].Path.as_deref(), Some("/dev/shm/buf (deleted)"));
+
+        assert_eq!(maps[3].Path.as_deref(), Some("[stack]"));
+    }
+
+    #[test]
+    fn linux_maps_bad_line() {
+        let text = b"00400000-00452000 r-xp 00000000 08:02 173521 /usr/bin/app\nbogus\n";
+
+        let error = parse_linux_maps(text, &whole(text)).err().unwrap();
+        match error.root_cause() {
+            ParseError::InvalidData(msg) => assert_eq!(*msg, "Truncated maps line"),
+            e => panic!("unexpected error {}", e),
+        }
+    }
+
+    // Fill consecutive registers with base, base + 1, ...
+    fn fill_u32(raw: &mut [u8], offset: usize, count: usize, base: u32) {
+        for i in 0..count {
+            put_u32(raw, offset + 4 * i, base + i as u32);
+        }
+    }
+
+    fn fill_u64(raw: &mut [u8], offset: usize, count: usize, base: u64) {
+        for i in 0..count {
+            put_u64(raw, offset + 8 * i, base + i as u64);
+        }
     }
 
-    Ok((system_info, seek_remain))
+    #[test]
+    fn context_arm64() {
+        let mut raw = vec![0; 912];
+        put_u32(&mut raw, 0, CONTEXT_ARM64 | CONTEXT_CONTROL);
+        put_u32(&mut raw, 4, 0x60000000);
+        fill_u64(&mut raw, 8, 33, 0x1000);
+        fill_u64(&mut raw, 272, 64, 0x2000);
+        put_u32(&mut raw, 784, 0x11);
+        put_u32(&mut raw, 788, 0x22);
+
+        let (context, _) = parse_thread_context_arm64(&raw, &whole(&raw)).unwrap();
+        assert_eq!(context.ContextFlags, CONTEXT_ARM64 | CONTEXT_CONTROL);
+        assert_eq!(context.Cpsr, 0x60000000);
+        assert_eq!(context.X.len(), 29);
+        assert_eq!((context.X[0], context.X[28]), (0x1000, 0x101c));
+        assert_eq!((context.Fp, context.Lr), (0x101d, 0x101e));
+        assert_eq!((context.Sp, context.Pc), (0x101f, 0x1020));
+        assert_eq!((context.Fpcr, context.Fpsr), (0x11, 0x22));
+        assert_eq!(context.V.len(), 32);
+        assert_eq!((context.V[0].Low, context.V[0].High), (0x2000, 0x2001));
+        assert_eq!((context.V[31].Low, context.V[31].High), (0x203e, 0x203f));
+
+        // Padding after the structure is allowed, truncation is not
+        raw.resize(1024, 0);
+        assert!(parse_thread_context_arm64(&raw, &whole(&raw)).is_ok());
+        assert!(parse_thread_context_arm64(&raw[..911], &whole(&raw[..911])).is_err());
+    }
+
+    #[test]
+    fn context_arm64_breakpad() {
+        let mut raw = vec![0; 796];
+        put_u32(&mut raw, 0, MD_CONTEXT_ARM64_OLD | CONTEXT_CONTROL);
+        fill_u64(&mut raw, 8, 33, 0x1000);
+        put_u32(&mut raw, 272, 0x60000000);
+        put_u32(&mut raw, 276, 0x22);
+        put_u32(&mut raw, 280, 0x11);
+        fill_u64(&mut raw, 284, 64, 0x2000);
+
+        let (context, _) = parse_thread_context_arm64_old(&raw, &whole(&raw)).unwrap();
+        assert_eq!(context.ContextFlags, MD_CONTEXT_ARM64_OLD | CONTEXT_CONTROL);
+        assert_eq!(context.Cpsr, 0x60000000);
+        assert_eq!((context.X[0], context.X[28]), (0x1000, 0x101c));
+        assert_eq!((context.Fp, context.Lr), (0x101d, 0x101e));
+        assert_eq!((context.Sp, context.Pc), (0x101f, 0x1020));
+        assert_eq!((context.Fpcr, context.Fpsr), (0x11, 0x22));
+        assert_eq!((context.V[31].Low, context.V[31].High), (0x203e, 0x203f));
+
+        assert!(parse_thread_context_arm64_old(&raw[..795], &whole(&raw[..795])).is_err());
+    }
+
+    #[test]
+    fn context_arm() {
+        let mut raw = vec![0; 416];
+        put_u32(&mut raw, 0, CONTEXT_ARM | CONTEXT_CONTROL);
+        fill_u32(&mut raw, 4, 17, 0x1000);
+        put_u32(&mut raw, 72, 0x33);
+        fill_u64(&mut raw, 80, 32, 0x2000);
+
+        let (context, _) = parse_thread_context_arm(&raw, &whole(&raw)).unwrap();
+        assert_eq!(context.ContextFlags, CONTEXT_ARM | CONTEXT_CONTROL);
+        assert_eq!(context.R.len(), 13);
+        assert_eq!((context.R[0], context.R[12]), (0x1000, 0x100c));
+        assert_eq!((context.Sp, context.Lr, context.Pc), (0x100d, 0x100e, 0x100f));
+        assert_eq!(context.Cpsr, 0x1010);
+        assert_eq!(context.Fpscr, 0x33);
+        assert_eq!((context.D[0], context.D[31]), (0x2000, 0x201f));
+
+        assert!(parse_thread_context_arm(&raw[..415], &whole(&raw[..415])).is_err());
+    }
+
+    #[test]
+    fn context_arm_breakpad() {
+        let mut raw = vec![0; 368];
+        put_u32(&mut raw, 0, MD_CONTEXT_ARM | CONTEXT_CONTROL);
+        fill_u32(&mut raw, 4, 17, 0x1000);
+        put_u64(&mut raw, 72, 0x33);
+        fill_u64(&mut raw, 80, 32, 0x2000);
+
+        let (context, _) = parse_thread_context_arm_breakpad(&raw, &whole(&raw)).unwrap();
+        assert_eq!(context.ContextFlags, MD_CONTEXT_ARM | CONTEXT_CONTROL);
+        assert_eq!((context.R[0], context.R[12]), (0x1000, 0x100c));
+        assert_eq!((context.Sp, context.Lr, context.Pc), (0x100d, 0x100e, 0x100f));
+        assert_eq!(context.Cpsr, 0x1010);
+        assert_eq!(context.Fpscr, 0x33);
+        assert_eq!((context.D[0], context.D[31]), (0x2000, 0x201f));
+
+        // The Breakpad layout is shorter than the Windows one
+        assert!(parse_thread_context_arm(&raw, &whole(&raw)).is_err());
+        assert!(parse_thread_context_arm_breakpad(&raw[..367], &whole(&raw[..367])).is_err());
+    }
+
+    const CONTEXT_ALL_X86: u32 = CONTEXT_CONTROL
+        | CONTEXT_INTEGER
+        | CONTEXT_SEGMENTS
+        | CONTEXT_FLOATING_POINT
+        | CONTEXT_DEBUG_REGISTERS
+        | CONTEXT_EXTENDED_REGISTERS;
+
+    fn x86_context(ContextFlags: u32) -> Vec<u8> {
+        let mut raw = vec![0; 716];
+        put_u32(&mut raw, 0, ContextFlags);
+        fill_u32(&mut raw, 4, 6, 0x100);
+        put_u32(&mut raw, 28, 0x37f);
+        put_u32(&mut raw, 136, 0x44);
+        fill_u32(&mut raw, 140, 16, 0x1000);
+        put_u32(&mut raw, 204 + 24, 0x1f80);
+        fill_u64(&mut raw, 204 + 160, 16, 0x2000);
+        raw
+    }
+
+    #[test]
+    fn context_x86() {
+        let raw = x86_context(CONTEXT_X86 | CONTEXT_ALL_X86);
+
+        let (context, _) = parse_thread_context_x86(&raw, &whole(&raw)).unwrap();
+        assert_eq!((context.Dr0, context.Dr7), (Some(0x100), Some(0x105)));
+        assert_eq!((context.SegGs, context.SegDs), (Some(0x1000), Some(0x1003)));
+        assert_eq!((context.Edi, context.Eax), (Some(0x1004), Some(0x1009)));
+        assert_eq!((context.Ebp, context.Eip), (Some(0x100a), Some(0x100b)));
+        assert_eq!((context.SegCs, context.EFlags), (Some(0x100c), Some(0x100d)));
+        assert_eq!((context.Esp, context.SegSs), (Some(0x100e), Some(0x100f)));
+
+        let float_save = context.FloatSave.unwrap();
+        assert_eq!(float_save.ControlWord, 0x37f);
+        assert_eq!(float_save.Cr0NpxState, 0x44);
+
+        let extended = context.ExtendedRegisters.unwrap();
+        assert_eq!(extended.MxCsr, 0x1f80);
+        assert_eq!(extended.XmmRegisters.len(), 8);
+        assert_eq!(extended.XmmRegisters[7].Low, 0x200e);
+    }
+
+    #[test]
+    fn context_x86_flags() {
+        let raw = x86_context(CONTEXT_X86 | CONTEXT_CONTROL);
+
+        let (context, _) = parse_thread_context_x86(&raw, &whole(&raw)).unwrap();
+        assert_eq!((context.Eip, context.Esp), (Some(0x100b), Some(0x100e)));
+        assert_eq!((context.SegCs, context.SegSs), (Some(0x100c), Some(0x100f)));
+        assert_eq!((context.Eax, context.Edi), (None, None));
+        assert_eq!((context.SegGs, context.SegDs), (None, None));
+        assert_eq!((context.Dr0, context.Dr7), (None, None));
+        assert!(context.FloatSave.is_none());
+        assert!(context.ExtendedRegisters.is_none());
+
+        assert!(parse_thread_context_x86(&raw[..715], &whole(&raw[..715])).is_err());
+    }
+
+    const CONTEXT_ALL_X64: u32 = CONTEXT_CONTROL
+        | CONTEXT_INTEGER
+        | CONTEXT_SEGMENTS
+        | CONTEXT_FLOATING_POINT
+        | CONTEXT_DEBUG_REGISTERS;
+
+    fn x64_context(ContextFlags: u32) -> Vec<u8> {
+        let mut raw = vec![0; 1232];
+        fill_u64(&mut raw, 0, 6, 0xdead);
+        put_u32(&mut raw, 48, ContextFlags);
+        put_u32(&mut raw, 52, 0x1f80);
+        for i in 0..6 {
+            LittleEndian::write_u16(&mut raw[56 + 2 * i..], 0x10 + i as u16);
+        }
+        put_u32(&mut raw, 68, 0x246);
+        fill_u64(&mut raw, 72, 6, 0x100);
+        fill_u64(&mut raw, 120, 17, 0x1000);
+        put_u32(&mut raw, 256 + 24, 0x1f80);
+        fill_u64(&mut raw, 256 + 160, 32, 0x2000);
+        fill_u64(&mut raw, 1192, 5, 0x3000);
+        raw
+    }
+
+    #[test]
+    fn context_x64() {
+        let raw = x64_context(CONTEXT_AMD64 | CONTEXT_ALL_X64);
+
+        let (context, _) = parse_thread_context_x64(&raw, &whole(&raw), None).unwrap();
+        assert_eq!(context.MxCsr, Some(0x1f80));
+        assert_eq!((context.SegCs, context.SegDs), (Some(0x10), Some(0x11)));
+        assert_eq!((context.SegGs, context.SegSs), (Some(0x14), Some(0x15)));
+        assert_eq!(context.EFlags, Some(0x246));
+        assert_eq!((context.Dr0, context.Dr7), (Some(0x100), Some(0x105)));
+        assert_eq!((context.Rax, context.Rsp), (Some(0x1000), Some(0x1004)));
+        assert_eq!((context.Rdi, context.R15), (Some(0x1007), Some(0x100f)));
+        assert_eq!(context.Rip, Some(0x1010));
+        assert_eq!(context.DebugControl, Some(0x3000));
+        assert_eq!(context.LastExceptionFromRip, Some(0x3004));
+
+        let flt_save = context.FltSave.unwrap();
+        assert_eq!(flt_save.MxCsr, 0x1f80);
+        assert_eq!(flt_save.XmmRegisters.len(), 16);
+        assert_eq!(flt_save.XmmRegisters[15].High, 0x201f);
+
+        assert!(context.XState.is_none());
+    }
+
+    #[test]
+    fn context_x64_flags() {
+        let raw = x64_context(CONTEXT_AMD64 | CONTEXT_INTEGER);
+
+        let (context, _) = parse_thread_context_x64(&raw, &whole(&raw), None).unwrap();
+        assert_eq!((context.Rax, context.R15), (Some(0x1000), Some(0x100f)));
+        assert_eq!((context.Rsp, context.Rip), (None, None));
+        assert_eq!((context.SegCs, context.SegDs), (None, None));
+        assert_eq!((context.EFlags, context.MxCsr), (None, None));
+        assert_eq!((context.Dr0, context.DebugControl), (None, None));
+        assert!(context.FltSave.is_none());
+
+        assert!(parse_thread_context_x64(&raw[..1231], &whole(&raw[..1231]), None).is_err());
+    }
+
+    const XSTATE_AVX512: u64 =
+        1 << XSTATE_AVX512_KMASK | 1 << XSTATE_AVX512_ZMM_H | 1 << XSTATE_AVX512_ZMM;
+
+    // An x64 context followed by a CONTEXT_EX whose XState chunk starts right
+    // after it, and an XSAVE area of the given length from its header
+    fn xstate_context(Mask: u64, CompactionMask: u64, SizeOfArea: usize) -> Vec<u8> {
+        let mut raw = x64_context(CONTEXT_AMD64 | CONTEXT_ALL_X64 | CONTEXT_XSTATE);
+        raw.resize(1232 + 24 + SizeOfArea, 0);
+        put_u32(&mut raw, 1232 + 16, 24);
+        put_u32(&mut raw, 1232 + 20, SizeOfArea as u32);
+        put_u64(&mut raw, 1256, Mask);
+        put_u64(&mut raw, 1256 + 8, CompactionMask);
+        raw
+    }
+
+    // Fill the AVX and AVX-512 state at offsets from the XSAVE header
+    fn fill_xstate(raw: &mut [u8], avx: usize, opmask: usize, zmm_h: usize, zmm: usize) {
+        fill_u64(raw, 1256 + avx, 32, 0x3000);
+        fill_u64(raw, 1256 + opmask, 8, 0x4000);
+        fill_u64(raw, 1256 + zmm_h, 64, 0x5000);
+        fill_u64(raw, 1256 + zmm, 128, 0x6000);
+    }
+
+    fn msc_info(features: &[(usize, u32, u32)]) -> XStateConfigFeatureMscInfo {
+        let mut config = XStateConfigFeatureMscInfo {
+            SizeOfInfo: 528,
+            ContextSize: 0,
+            EnabledFeatures: 0,
+            Features: (0..64).map(|_| XStateFeature { Offset: 0, Size: 0 }).collect(),
+        };
+        for &(i, Offset, Size) in features {
+            config.EnabledFeatures |= 1 << i;
+            config.Features[i] = XStateFeature { Offset, Size };
+        }
+        config
+    }
+
+    fn assert_xstate_registers(xstate: &XStateRegisters) {
+        let ymm = xstate.Ymm.as_ref().unwrap();
+        assert_eq!(ymm.len(), 16);
+        assert_eq!((ymm[0][0].Low, ymm[0][1].Low), (0x2000, 0x3000));
+        assert_eq!((ymm[15][0].High, ymm[15][1].High), (0x201f, 0x301f));
+
+        assert_eq!(xstate.OpMask.as_ref().unwrap()[7], 0x4007);
+
+        let zmm = xstate.Zmm.as_ref().unwrap();
+        assert_eq!(zmm.len(), 32);
+        assert_eq!((zmm[0][1].Low, zmm[0][2].Low), (0x3000, 0x5000));
+        assert_eq!(zmm[15][3].High, 0x503f);
+        assert_eq!((zmm[16][0].Low, zmm[31][3].High), (0x6000, 0x607f));
+    }
+
+    #[test]
+    fn xstate_standard() {
+        let mut raw = xstate_context(0x7 | XSTATE_AVX512, 0, 2176);
+        fill_xstate(&mut raw, 64, 576, 640, 1152);
+
+        let (context, _) = parse_thread_context_x64(&raw, &whole(&raw), None).unwrap();
+        let xstate = context.XState.unwrap();
+        assert_eq!(xstate.Mask, 0x7 | XSTATE_AVX512);
+        assert_xstate_registers(&xstate);
+    }
+
+    #[test]
+    fn xstate_initial_state() {
+        // AVX-512 was captured but is in its initial state
+        let mut raw = xstate_context(0x7, 0, 2176);
+        fill_xstate(&mut raw, 64, 576, 640, 1152);
+
+        let config = msc_info(&[(XSTATE_AVX, 576, 256), (XSTATE_AVX512_KMASK, 1088, 64)]);
+        let (context, _) = parse_thread_context_x64(&raw, &whole(&raw), Some(&config)).unwrap();
+        let xstate = context.XState.unwrap();
+        assert_eq!(xstate.Ymm.unwrap()[0][1].Low, 0x3000);
+        assert_eq!(xstate.OpMask, Some(vec![0; 8]));
+        assert!(xstate.Zmm.is_none());
+    }
+
+    #[test]
+    fn xstate_compacted() {
+        // An MPX feature of an unusual size leaves the AVX-512 features to be
+        // realigned to 64 bytes
+        let features = [
+            (XSTATE_AVX, 0, 256),
+            (3, 0, 32),
+            (XSTATE_AVX512_KMASK, 0, 64),
+            (XSTATE_AVX512_ZMM_H, 0, 512),
+            (XSTATE_AVX512_ZMM, 0, 1024),
+        ];
+        let config = msc_info(&features);
+
+        let CompactionMask = XSTATE_COMPACTION_ENABLE | config.EnabledFeatures | 0x3;
+        let mut raw = xstate_context(0x7 | XSTATE_AVX512, CompactionMask, 1984);
+        fill_xstate(&mut raw, 64, 384, 448, 960);
+
+        let (context, _) = parse_thread_context_x64(&raw, &whole(&raw), Some(&config)).unwrap();
+        assert_xstate_registers(&context.XState.unwrap());
+    }
+
+    #[test]
+    fn xstate_malformed() {
+        // The XState chunk claims more data than the context holds
+        let mut raw = xstate_context(0x7, 0, 2176);
+        put_u32(&mut raw, 1232 + 20, u32::MAX);
+
+        let (context, _) = parse_thread_context_x64(&raw, &whole(&raw), None).unwrap();
+        assert!(context.XState.is_none());
+        assert_eq!(context.Rip, Some(0x1010));
+
+        // Feature offsets and sizes beyond any addressable area
+        let mut raw = xstate_context(0x7, 0, 2176);
+        fill_xstate(&mut raw, 64, 576, 640, 1152);
+        let config = msc_info(&[(XSTATE_AVX, u32::MAX, u32::MAX)]);
+
+        let (context, _) = parse_thread_context_x64(&raw, &whole(&raw), Some(&config)).unwrap();
+        assert!(context.XState.unwrap().Ymm.is_none());
+    }
+
+    fn context_info(arch: u16) -> ContextInfo<'static> {
+        ContextInfo {
+            ProcessorArchitecture: Some(arch),
+            XStateData: None,
+        }
+    }
+
+    #[test]
+    fn context_selection_amd64() {
+        let info = context_info(PROCESSOR_ARCHITECTURE_AMD64);
+
+        // Register home values which look like other CPU type bits
+        let mut raw = x64_context(CONTEXT_AMD64 | CONTEXT_ALL_X64);
+        put_u32(&mut raw, 0, CONTEXT_X86 | CONTEXT_ARM);
+        match checked_thread_context(&raw, &whole(&raw), info) {
+            (MaybeThreadContext::X64(context), None) => assert_eq!(context.Rip, Some(0x1010)),
+            (_, error) => panic!("x64 context not decoded: {:?}", error),
+        }
+
+        // x86 contexts are too short, and other CPUs name themselves
+        let x86 = x86_context(CONTEXT_X86 | CONTEXT_ALL_X86);
+        let arm64 = x64_context(CONTEXT_ARM64 | CONTEXT_CONTROL);
+        for raw in [&x86, &arm64].iter() {
+            match context_layout(raw, info) {
+                Err(ParseError::ContextMismatch { architecture, .. }) => {
+                    assert_eq!(architecture, PROCESSOR_ARCHITECTURE_AMD64)
+                }
+                _ => panic!("mismatch was not reported"),
+            }
+        }
+    }
+
+    #[test]
+    fn context_selection_per_thread() {
+        let info = context_info(PROCESSOR_ARCHITECTURE_AMD64);
+
+        // MINIDUMP_THREAD_LIST of a good thread and one with an x86 context
+        let x64 = x64_context(CONTEXT_AMD64 | CONTEXT_ALL_X64);
+        let x86 = x86_context(CONTEXT_X86 | CONTEXT_ALL_X86);
+        let mut raw = vec![0; 4 + 2 * 48];
+        put_u32(&mut raw, 0, 2);
+        for (i, context) in [&x64, &x86].iter().enumerate() {
+            let entry = 4 + 48 * i;
+            put_u32(&mut raw, entry, 10 + i as u32);
+            put_u32(&mut raw, entry + 40, context.len() as u32);
+            let rva = raw.len() as u32;
+            put_u32(&mut raw, entry + 44, rva);
+            raw.extend_from_slice(context);
+        }
+
+        let loc = LocationDescriptor {
+            Offset: 0,
+            Length: 4 + 2 * 48,
+        };
+        let (threads, _) = parse_thread_list(&raw, &loc, info).unwrap();
+        assert_eq!(threads.len(), 2);
+
+        assert!(threads[0].ContextError.is_none());
+        match threads[0].Context {
+            MaybeThreadContext::X64(_) => (),
+            _ => panic!("x64 context not decoded"),
+        }
+
+        assert!(threads[1].ContextError.is_some());
+        match threads[1].Context {
+            MaybeThreadContext::None => (),
+            _ => panic!("mismatched context was decoded"),
+        }
+    }
 }
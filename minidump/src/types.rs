@@ -1,5 +1,9 @@
 #![allow(non_snake_case)]
 
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+
 pub struct Header {
     pub Version: u16,
     pub NumberOfStreams: u32,
@@ -8,7 +12,7 @@ pub struct Header {
     pub Flags: u64,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Copy, Debug, Serialize)]
 pub struct LocationDescriptor {
     pub Offset: u64,
     pub Length: u64,
@@ -20,7 +24,7 @@ pub struct OverlayDescriptor {
     pub Location: LocationDescriptor,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[allow(unused)]
 pub enum StreamType {
     UnusedStream = 0,
@@ -42,6 +46,69 @@ pub enum StreamType {
     HandleOperationListStream = 18,
     TokenStream = 19,
     JavaScriptDataStream = 20,
+    SystemMemoryInfoStream = 21,
+    ProcessVmCountersStream = 22,
+    ThreadNamesStream = 24,
+
+    // Breakpad extension streams
+    BreakpadInfoStream = 0x47670001,
+    AssertionInfoStream = 0x47670002,
+    LinuxCpuInfoStream = 0x47670003,
+    LinuxProcStatusStream = 0x47670004,
+    LinuxLsbReleaseStream = 0x47670005,
+    LinuxCmdLineStream = 0x47670006,
+    LinuxEnvironStream = 0x47670007,
+    LinuxAuxvStream = 0x47670008,
+    LinuxMapsStream = 0x47670009,
+    LinuxDsoDebugStream = 0x4767000A,
+
+    // Crashpad extension streams
+    CrashpadInfoStream = 0x43500001,
+}
+
+impl StreamType {
+    pub fn from_u32(value: u32) -> Option<StreamType> {
+        use self::StreamType::*;
+
+        let stream_type = match value {
+            0 => UnusedStream,
+            3 => ThreadListStream,
+            4 => ModuleListStream,
+            5 => MemoryListStream,
+            6 => ExceptionStream,
+            7 => SystemInfoStream,
+            8 => ThreadExListStream,
+            9 => Memory64ListStream,
+            10 => CommentStreamA,
+            11 => CommentStreamW,
+            12 => HandleDataStream,
+            13 => FunctionTableStream,
+            14 => UnloadedModuleListStream,
+            15 => MiscInfoStream,
+            16 => MemoryInfoListStream,
+            17 => ThreadInfoListStream,
+            18 => HandleOperationListStream,
+            19 => TokenStream,
+            20 => JavaScriptDataStream,
+            21 => SystemMemoryInfoStream,
+            22 => ProcessVmCountersStream,
+            24 => ThreadNamesStream,
+            0x47670001 => BreakpadInfoStream,
+            0x47670002 => AssertionInfoStream,
+            0x47670003 => LinuxCpuInfoStream,
+            0x47670004 => LinuxProcStatusStream,
+            0x47670005 => LinuxLsbReleaseStream,
+            0x47670006 => LinuxCmdLineStream,
+            0x47670007 => LinuxEnvironStream,
+            0x47670008 => LinuxAuxvStream,
+            0x47670009 => LinuxMapsStream,
+            0x4767000A => LinuxDsoDebugStream,
+            0x43500001 => CrashpadInfoStream,
+            _ => return None,
+        };
+
+        Some(stream_type)
+    }
 }
 
 pub struct Directory {
@@ -60,6 +127,91 @@ pub struct MemoryInfo {
     pub Type: u32,
 }
 
+pub struct Guid {
+    pub Data1: u32,
+    pub Data2: u16,
+    pub Data3: u16,
+    pub Data4: [u8; 8],
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-",
+            self.Data1, self.Data2, self.Data3, self.Data4[0], self.Data4[1]
+        )?;
+        for b in &self.Data4[2..] {
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Guid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+fn serialize_hex<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    serializer.serialize_str(&hex)
+}
+
+#[derive(Serialize)]
+pub struct FixedFileInfo {
+    pub Signature: u32,
+    pub StrucVersion: u32,
+    pub FileVersionMS: u32,
+    pub FileVersionLS: u32,
+    pub ProductVersionMS: u32,
+    pub ProductVersionLS: u32,
+    pub FileFlagsMask: u32,
+    pub FileFlags: u32,
+    pub FileOS: u32,
+    pub FileType: u32,
+    pub FileSubtype: u32,
+    pub FileDateMS: u32,
+    pub FileDateLS: u32,
+
+    pub FileVersion: String,
+    pub ProductVersion: String,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+pub enum CodeViewRecord {
+    // RSDS record pointing to a PDB 7.0 file
+    Pdb70 {
+        Signature: Guid,
+        Age: u32,
+        PdbFileName: String,
+    },
+    // NB10 record pointing to a PDB 2.0 file
+    Pdb20 {
+        Offset: u32,
+        Signature: u32,
+        Age: u32,
+        PdbFileName: String,
+    },
+    // Breakpad record holding the GNU build-id of an ELF file
+    Elf {
+        #[serde(serialize_with = "serialize_hex")]
+        BuildId: Vec<u8>,
+    },
+    Unknown {
+        CvSignature: u32,
+    },
+}
+
+#[derive(Serialize)]
+pub struct MiscRecord {
+    pub DataType: u32,
+    pub Unicode: bool,
+    pub Data: String,
+}
+
 #[derive(Serialize)]
 pub struct Module {
     pub BaseOfImage: u64,
@@ -67,52 +219,686 @@ pub struct Module {
     pub CheckSum: u32,
     pub TimeDateStamp: u32,
     pub ModuleNameRva: u32,
+    pub VersionInfo: Option<FixedFileInfo>,
+    pub CvRecord: LocationDescriptor,
+    pub MiscRecord: LocationDescriptor,
 
     pub ModuleName: Option<String>,
+    pub CodeView: Option<CodeViewRecord>,
+    pub Misc: Option<MiscRecord>,
+
+    // Identifiers used by symbol servers to locate debug and binary files
+    pub DebugIdentifier: Option<String>,
+    pub CodeIdentifier: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UnloadedModule {
+    pub BaseOfImage: u64,
+    pub SizeOfImage: u32,
+    pub CheckSum: u32,
+    pub TimeDateStamp: u32,
+    pub ModuleNameRva: u32,
+
+    pub ModuleName: Option<String>,
+}
+
+// The module, if any, which contains an address
+#[derive(Serialize)]
+#[serde(tag = "type")]
+pub enum AddressLocation {
+    Module {
+        ModuleName: Option<String>,
+        BaseOfImage: u64,
+        Offset: u64,
+    },
+    UnloadedModule {
+        ModuleName: Option<String>,
+        BaseOfImage: u64,
+        Offset: u64,
+    },
+    // Code registered at runtime with a dynamic function table
+    FunctionTable {
+        MinimumAddress: u64,
+        Offset: u64,
+    },
+}
+
+impl fmt::Display for AddressLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (prefix, name, offset) = match *self {
+            AddressLocation::FunctionTable {
+                MinimumAddress,
+                Offset,
+            } => return write!(f, "dynamic code {:#x}+{:#x}", MinimumAddress, Offset),
+            AddressLocation::Module {
+                ref ModuleName,
+                Offset,
+                ..
+            } => ("", ModuleName, Offset),
+            AddressLocation::UnloadedModule {
+                ref ModuleName,
+                Offset,
+                ..
+            } => ("inside unloaded module ", ModuleName, Offset),
+        };
+
+        // Only show the file name of the module path
+        let name = name
+            .as_ref()
+            .and_then(|n| n.rsplit(['\\', '/']).next())
+            .unwrap_or("<unknown>");
+
+        write!(f, "{}{}+{:#x}", prefix, name, offset)
+    }
+}
+
+// Best-effort decoding of object information attached to a handle. Layouts
+// follow the native NT query structures, whose size depends on pointer width.
+#[derive(Serialize)]
+#[serde(tag = "type")]
+pub enum HandleObjectDetails {
+    Thread {
+        ExitStatus: u32,
+        TebBaseAddress: u64,
+        ProcessId: u64,
+        ThreadId: u64,
+        AffinityMask: u64,
+        Priority: i32,
+        BasePriority: i32,
+    },
+    Mutant {
+        CurrentCount: i32,
+        OwnedByCaller: bool,
+        AbandonedState: bool,
+    },
+    MutantOwner {
+        ProcessId: u64,
+        ThreadId: u64,
+    },
+    Process {
+        ExitStatus: u32,
+        PebBaseAddress: u64,
+        AffinityMask: u64,
+        BasePriority: i32,
+        ProcessId: u64,
+        ParentProcessId: u64,
+    },
+    Section {
+        BaseAddress: u64,
+        AllocationAttributes: u32,
+        MaximumSize: u64,
+    },
+}
+
+#[derive(Serialize)]
+pub struct HandleObjectInformation {
+    pub NextInfoRva: u32,
+    pub InfoType: u32,
+    pub SizeOfInfo: u32,
+    #[serde(serialize_with = "serialize_hex")]
+    pub Data: Vec<u8>,
+
+    pub Details: Option<HandleObjectDetails>,
+}
+
+#[derive(Serialize)]
+pub struct HandleDescriptor {
+    pub Handle: u64,
+    pub TypeNameRva: u32,
+    pub ObjectNameRva: u32,
+    pub Attributes: u32,
+    pub GrantedAccess: u32,
+    pub HandleCount: u32,
+    pub PointerCount: u32,
+    pub ObjectInfoRva: u32,
+
+    pub TypeName: Option<String>,
+    pub ObjectName: Option<String>,
+    pub ObjectInfo: Vec<HandleObjectInformation>,
+}
+
+#[derive(Serialize)]
+pub struct TokenPrivilege {
+    pub Luid: u64,
+    pub Attributes: u32,
+
+    pub Name: Option<&'static str>,
+    pub Enabled: bool,
+}
+
+#[derive(Serialize)]
+pub struct TokenInfo {
+    pub TokenSize: u32,
+    pub TokenId: u32,
+    pub TokenHandle: u64,
+    #[serde(serialize_with = "serialize_hex")]
+    pub Data: Vec<u8>,
+
+    // Decoded from the token data, if it has the expected layout
+    pub User: Option<String>,
+    pub Groups: Vec<String>,
+    pub Privileges: Vec<TokenPrivilege>,
+    pub IntegrityLevel: Option<u32>,
+    pub IntegrityLevelName: Option<&'static str>,
+}
+
+// Application Verifier record of a handle being opened or closed
+#[derive(Serialize)]
+pub struct HandleOperation {
+    pub Handle: u64,
+    pub ProcessId: u32,
+    pub ThreadId: u32,
+    pub OperationType: u32,
+    pub BackTraceIndex: u32,
+    pub BackTrace: Vec<u64>,
 }
 
+#[derive(Serialize)]
+#[serde(tag = "type")]
+pub enum RuntimeFunction {
+    // x64 RUNTIME_FUNCTION with addresses relative to the table base
+    X64 {
+        BeginAddress: u32,
+        EndAddress: u32,
+        UnwindInfoAddress: u32,
+    },
+    // ARM and ARM64 RUNTIME_FUNCTION with packed or relative unwind data
+    Arm {
+        BeginAddress: u32,
+        UnwindData: u32,
+    },
+    Unknown {
+        #[serde(serialize_with = "serialize_hex")]
+        Data: Vec<u8>,
+    },
+}
+
+#[derive(Serialize)]
+pub struct FunctionTable {
+    pub MinimumAddress: u64,
+    pub MaximumAddress: u64,
+    pub BaseAddress: u64,
+    pub EntryCount: u32,
+    pub SizeOfAlignPad: u32,
+    #[serde(serialize_with = "serialize_hex")]
+    pub NativeDescriptor: Vec<u8>,
+
+    pub Entries: Vec<RuntimeFunction>,
+}
+
+impl FunctionTable {
+    pub fn contains(&self, addr: u64) -> bool {
+        addr >= self.MinimumAddress && addr < self.MaximumAddress
+    }
+
+    // Find the function entry covering an address. Only entries which record
+    // their end address can be matched.
+    pub fn lookup(&self, addr: u64) -> Option<&RuntimeFunction> {
+        let rva = addr.checked_sub(self.BaseAddress)?;
+
+        self.Entries.iter().find(|entry| match **entry {
+            RuntimeFunction::X64 {
+                BeginAddress,
+                EndAddress,
+                ..
+            } => rva >= BeginAddress as u64 && rva < EndAddress as u64,
+            _ => false,
+        })
+    }
+}
+
+#[derive(Serialize)]
+pub struct SystemTime {
+    pub Year: u16,
+    pub Month: u16,
+    pub DayOfWeek: u16,
+    pub Day: u16,
+    pub Hour: u16,
+    pub Minute: u16,
+    pub Second: u16,
+    pub Milliseconds: u16,
+}
+
+#[derive(Serialize)]
+pub struct TimeZoneInformation {
+    pub Bias: i32,
+    pub StandardName: String,
+    pub StandardDate: SystemTime,
+    pub StandardBias: i32,
+    pub DaylightName: String,
+    pub DaylightDate: SystemTime,
+    pub DaylightBias: i32,
+}
+
+#[derive(Serialize)]
+pub struct XStateFeature {
+    pub Offset: u32,
+    pub Size: u32,
+}
+
+#[derive(Serialize)]
+pub struct XStateConfigFeatureMscInfo {
+    pub SizeOfInfo: u32,
+    pub ContextSize: u32,
+    pub EnabledFeatures: u64,
+    pub Features: Vec<XStateFeature>,
+}
+
+// Fields are None if the stream version does not include them, or if Flags1
+// marks them as invalid.
+#[derive(Serialize)]
+pub struct MiscInfo {
+    pub SizeOfInfo: u32,
+    pub Flags1: u32,
+    pub ProcessId: Option<u32>,
+    pub ProcessCreateTime: Option<u32>,
+    pub ProcessUserTime: Option<u32>,
+    pub ProcessKernelTime: Option<u32>,
+    pub ProcessorMaxMhz: Option<u32>,
+    pub ProcessorCurrentMhz: Option<u32>,
+    pub ProcessorMhzLimit: Option<u32>,
+    pub ProcessorMaxIdleState: Option<u32>,
+    pub ProcessorCurrentIdleState: Option<u32>,
+    pub ProcessIntegrityLevel: Option<u32>,
+    pub ProcessExecuteFlags: Option<u32>,
+    pub ProtectedProcess: Option<u32>,
+    pub TimeZoneId: Option<u32>,
+    pub TimeZone: Option<TimeZoneInformation>,
+    pub BuildString: Option<String>,
+    pub DbgBldStr: Option<String>,
+    pub XStateData: Option<XStateConfigFeatureMscInfo>,
+    pub ProcessCookie: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct AuxvEntry {
+    pub Type: u64,
+    pub Value: u64,
+
+    pub Name: Option<&'static str>,
+}
+
+// A line of /proc/self/maps
+#[derive(Serialize)]
+pub struct LinuxMapping {
+    pub Start: u64,
+    pub End: u64,
+    pub Permissions: String,
+    pub Offset: u64,
+    pub Device: String,
+    pub Inode: u64,
+    pub Path: Option<String>,
+}
+
+impl LinuxMapping {
+    pub fn readable(&self) -> bool {
+        self.Permissions.starts_with('r')
+    }
+
+    pub fn writable(&self) -> bool {
+        self.Permissions.chars().nth(1) == Some('w')
+    }
+
+    pub fn executable(&self) -> bool {
+        self.Permissions.chars().nth(2) == Some('x')
+    }
+
+    pub fn shared(&self) -> bool {
+        self.Permissions.chars().nth(3) == Some('s')
+    }
+}
+
+#[derive(Serialize)]
+pub struct LinuxLinkMap {
+    pub Addr: u64,
+    pub Name: u32,
+    pub Ld: u64,
+
+    pub ModuleName: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct LinuxDsoDebug {
+    pub Version: u32,
+    pub Map: u32,
+    pub DsoCount: u32,
+    pub Brk: u64,
+    pub LdBase: u64,
+    pub Dynamic: u64,
+
+    pub LinkMaps: Vec<LinuxLinkMap>,
+}
+
+#[derive(Serialize)]
+pub struct BreakpadInfo {
+    pub Validity: u32,
+    pub DumpThreadId: Option<u32>,
+    pub RequestingThreadId: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub struct AssertionInfo {
+    pub Expression: String,
+    pub Function: String,
+    pub File: String,
+    pub Line: u32,
+    pub Type: u32,
+}
+
+#[derive(Serialize)]
+pub struct CrashpadAnnotation {
+    pub Name: String,
+    pub Type: u16,
+    #[serde(serialize_with = "serialize_hex")]
+    pub Data: Vec<u8>,
+
+    // Decoded value of string annotations
+    pub Value: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ModuleCrashpadInfo {
+    pub ModuleListIndex: u32,
+    pub Version: u32,
+    pub ListAnnotations: Vec<String>,
+    pub SimpleAnnotations: BTreeMap<String, String>,
+    pub AnnotationObjects: Vec<CrashpadAnnotation>,
+
+    pub ModuleName: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CrashpadInfo {
+    pub Version: u32,
+    pub ReportId: Guid,
+    pub ClientId: Guid,
+    pub SimpleAnnotations: BTreeMap<String, String>,
+    pub ModuleList: Vec<ModuleCrashpadInfo>,
+}
+
+#[derive(Serialize)]
+pub struct SystemBasicInformation {
+    pub TimerResolution: u32,
+    pub PageSize: u32,
+    pub NumberOfPhysicalPages: u32,
+    pub LowestPhysicalPageNumber: u32,
+    pub HighestPhysicalPageNumber: u32,
+    pub AllocationGranularity: u32,
+    pub MinimumUserModeAddress: u64,
+    pub MaximumUserModeAddress: u64,
+    pub ActiveProcessorsAffinityMask: u64,
+    pub NumberOfProcessors: u32,
+}
+
+#[derive(Serialize)]
+pub struct SystemFileCacheInformation {
+    pub CurrentSize: u64,
+    pub PeakSize: u64,
+    pub PageFaultCount: u32,
+    pub MinimumWorkingSet: u64,
+    pub MaximumWorkingSet: u64,
+    pub CurrentSizeIncludingTransitionInPages: u64,
+    pub PeakSizeIncludingTransitionInPages: u64,
+    pub TransitionRePurposeCount: u32,
+    pub Flags: u32,
+}
+
+#[derive(Serialize)]
+pub struct SystemBasicPerformanceInformation {
+    pub AvailablePages: u64,
+    pub CommittedPages: u64,
+    pub CommitLimit: u64,
+    pub PeakCommitment: u64,
+}
+
+// Selected fields of MINIDUMP_SYSTEM_PERFORMANCE_INFORMATION
+#[derive(Serialize)]
+pub struct SystemPerformanceInformation {
+    pub IdleProcessTime: u64,
+    pub IoReadTransferCount: u64,
+    pub IoWriteTransferCount: u64,
+    pub IoOtherTransferCount: u64,
+    pub AvailablePages: u32,
+    pub CommittedPages: u32,
+    pub CommitLimit: u32,
+    pub PeakCommitment: u32,
+    pub PageFaultCount: u32,
+    pub PagedPoolPages: u32,
+    pub NonPagedPoolPages: u32,
+    pub ContextSwitches: u32,
+    pub SystemCalls: u32,
+    pub ResidentAvailablePages: i64,
+    pub SharedCommittedPages: u64,
+}
+
+#[derive(Serialize)]
+pub struct SystemMemoryInfo {
+    pub Revision: u16,
+    pub Flags: u16,
+    pub BasicInfo: SystemBasicInformation,
+    pub FileCacheInfo: SystemFileCacheInformation,
+    pub BasicPerfInfo: SystemBasicPerformanceInformation,
+    pub PerfInfo: SystemPerformanceInformation,
+}
+
+#[derive(Serialize)]
+pub struct ProcessVmCounters {
+    pub Revision: u16,
+    pub Flags: u16,
+    pub PageFaultCount: Option<u32>,
+    pub PeakWorkingSetSize: Option<u64>,
+    pub WorkingSetSize: Option<u64>,
+    pub QuotaPeakPagedPoolUsage: Option<u64>,
+    pub QuotaPagedPoolUsage: Option<u64>,
+    pub QuotaPeakNonPagedPoolUsage: Option<u64>,
+    pub QuotaNonPagedPoolUsage: Option<u64>,
+    pub PagefileUsage: Option<u64>,
+    pub PeakPagefileUsage: Option<u64>,
+    pub PeakVirtualSize: Option<u64>,
+    pub VirtualSize: Option<u64>,
+    pub PrivateUsage: Option<u64>,
+    pub PrivateWorkingSetSize: Option<u64>,
+    pub SharedCommitUsage: Option<u64>,
+    pub JobSharedCommitUsage: Option<u64>,
+    pub JobPrivateCommitUsage: Option<u64>,
+    pub JobPeakPrivateCommitUsage: Option<u64>,
+    pub JobPrivateCommitLimit: Option<u64>,
+    pub JobTotalCommitLimit: Option<u64>,
+}
+
+// 128-bit vector register
+#[derive(Clone, Copy, Serialize)]
+pub struct M128A {
+    pub Low: u64,
+    pub High: u64,
+}
+
+// x87 state of the x86 CONTEXT
+#[derive(Serialize)]
+pub struct FloatingSaveArea {
+    pub ControlWord: u32,
+    pub StatusWord: u32,
+    pub TagWord: u32,
+    pub ErrorOffset: u32,
+    pub ErrorSelector: u32,
+    pub DataOffset: u32,
+    pub DataSelector: u32,
+    #[serde(serialize_with = "serialize_hex")]
+    pub RegisterArea: Vec<u8>,
+    pub Cr0NpxState: u32,
+}
+
+// FXSAVE format x87 and SSE state
+#[derive(Serialize)]
+pub struct XmmSaveArea32 {
+    pub ControlWord: u16,
+    pub StatusWord: u16,
+    pub TagWord: u8,
+    pub ErrorOpcode: u16,
+    pub ErrorOffset: u32,
+    pub ErrorSelector: u16,
+    pub DataOffset: u32,
+    pub DataSelector: u16,
+    pub MxCsr: u32,
+    pub MxCsr_Mask: u32,
+    pub FloatRegisters: Vec<M128A>,
+    pub XmmRegisters: Vec<M128A>,
+}
+
+// AVX and AVX-512 state recovered from the XSAVE area of CONTEXT_XSTATE.
+// Register sets are None if the feature was not captured.
+#[derive(Serialize)]
+pub struct XStateRegisters {
+    pub Mask: u64,
+    pub CompactionMask: u64,
+    pub Ymm: Option<Vec<[M128A; 2]>>,
+    pub OpMask: Option<Vec<u64>>,
+    pub Zmm: Option<Vec<[M128A; 4]>>,
+}
+
+// Register groups which ContextFlags does not mark as captured are None
 #[derive(Serialize)]
 pub struct ContextX86 {
-    pub EFlags: u32,
-    pub Eip: u32,
-    pub Eax: u32,
-    pub Ebx: u32,
-    pub Ecx: u32,
-    pub Edx: u32,
-    pub Esp: u32,
-    pub Ebp: u32,
-    pub Esi: u32,
-    pub Edi: u32,
+    pub ContextFlags: u32,
+    pub EFlags: Option<u32>,
+    pub Eip: Option<u32>,
+    pub Eax: Option<u32>,
+    pub Ebx: Option<u32>,
+    pub Ecx: Option<u32>,
+    pub Edx: Option<u32>,
+    pub Esp: Option<u32>,
+    pub Ebp: Option<u32>,
+    pub Esi: Option<u32>,
+    pub Edi: Option<u32>,
+    pub SegCs: Option<u32>,
+    pub SegSs: Option<u32>,
+    pub SegDs: Option<u32>,
+    pub SegEs: Option<u32>,
+    pub SegFs: Option<u32>,
+    pub SegGs: Option<u32>,
+    pub Dr0: Option<u32>,
+    pub Dr1: Option<u32>,
+    pub Dr2: Option<u32>,
+    pub Dr3: Option<u32>,
+    pub Dr6: Option<u32>,
+    pub Dr7: Option<u32>,
+    pub FloatSave: Option<FloatingSaveArea>,
+    pub ExtendedRegisters: Option<XmmSaveArea32>,
 }
 
+// Register groups which ContextFlags does not mark as captured are None
 #[derive(Serialize)]
 pub struct ContextX64 {
-    pub EFlags: u32,
-    pub Rip: u64,
-    pub Rax: u64,
-    pub Rbx: u64,
-    pub Rcx: u64,
-    pub Rdx: u64,
-    pub Rsp: u64,
-    pub Rbp: u64,
-    pub Rsi: u64,
-    pub Rdi: u64,
-    pub R8: u64,
-    pub R9: u64,
-    pub R10: u64,
-    pub R11: u64,
-    pub R12: u64,
-    pub R13: u64,
-    pub R14: u64,
-    pub R15: u64,
+    pub ContextFlags: u32,
+    pub EFlags: Option<u32>,
+    pub Rip: Option<u64>,
+    pub Rax: Option<u64>,
+    pub Rbx: Option<u64>,
+    pub Rcx: Option<u64>,
+    pub Rdx: Option<u64>,
+    pub Rsp: Option<u64>,
+    pub Rbp: Option<u64>,
+    pub Rsi: Option<u64>,
+    pub Rdi: Option<u64>,
+    pub R8: Option<u64>,
+    pub R9: Option<u64>,
+    pub R10: Option<u64>,
+    pub R11: Option<u64>,
+    pub R12: Option<u64>,
+    pub R13: Option<u64>,
+    pub R14: Option<u64>,
+    pub R15: Option<u64>,
+    pub SegCs: Option<u16>,
+    pub SegSs: Option<u16>,
+    pub SegDs: Option<u16>,
+    pub SegEs: Option<u16>,
+    pub SegFs: Option<u16>,
+    pub SegGs: Option<u16>,
+    pub Dr0: Option<u64>,
+    pub Dr1: Option<u64>,
+    pub Dr2: Option<u64>,
+    pub Dr3: Option<u64>,
+    pub Dr6: Option<u64>,
+    pub Dr7: Option<u64>,
+    pub DebugControl: Option<u64>,
+    pub LastBranchToRip: Option<u64>,
+    pub LastBranchFromRip: Option<u64>,
+    pub LastExceptionToRip: Option<u64>,
+    pub LastExceptionFromRip: Option<u64>,
+    pub MxCsr: Option<u32>,
+    pub FltSave: Option<XmmSaveArea32>,
+    pub XState: Option<XStateRegisters>,
+}
+
+#[derive(Serialize)]
+pub struct ContextArm {
+    pub ContextFlags: u32,
+    pub Cpsr: u32,
+    pub Pc: u32,
+    pub Sp: u32,
+    pub Lr: u32,
+    pub R: Vec<u32>, // R0-R12
+    pub Fpscr: u32,
+    pub D: Vec<u64>,
+}
+
+#[derive(Serialize)]
+pub struct ContextArm64 {
+    pub ContextFlags: u32,
+    pub Cpsr: u32,
+    pub Pc: u64,
+    pub Sp: u64,
+    pub Fp: u64,
+    pub Lr: u64,
+    pub X: Vec<u64>, // X0-X28
+    pub Fpcr: u32,
+    pub Fpsr: u32,
+    pub V: Vec<M128A>,
 }
 
 #[derive(Serialize)]
 #[serde(tag = "type")]
 pub enum MaybeThreadContext {
     None,
-    X86(ContextX86),
-    X64(ContextX64),
+    X86(Box<ContextX86>),
+    X64(Box<ContextX64>),
+    Arm(ContextArm),
+    Arm64(ContextArm64),
+}
+
+impl MaybeThreadContext {
+    pub fn instruction_pointer(&self) -> Option<u64> {
+        match *self {
+            MaybeThreadContext::None => None,
+            MaybeThreadContext::X86(ref ctx) => ctx.Eip.map(u64::from),
+            MaybeThreadContext::X64(ref ctx) => ctx.Rip,
+            MaybeThreadContext::Arm(ref ctx) => Some(ctx.Pc as u64),
+            MaybeThreadContext::Arm64(ref ctx) => Some(ctx.Pc),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct ThreadInfo {
+    pub ThreadId: u32,
+    pub DumpFlags: u32,
+    pub DumpError: u32,
+    pub ExitStatus: u32,
+    pub CreateTime: u64,
+    pub ExitTime: u64,
+    pub KernelTime: u64,
+    pub UserTime: u64,
+    pub StartAddress: u64,
+    pub Affinity: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ThreadName {
+    pub ThreadId: u32,
+    pub RvaOfThreadName: u64,
+
+    pub Name: String,
 }
 
 #[derive(Serialize)]
@@ -125,7 +911,17 @@ pub struct Thread {
     pub Stack: OverlayDescriptor,
     pub ThreadContext: LocationDescriptor,
 
+    // Only present in MINIDUMP_THREAD_EX entries
+    pub BackingStore: Option<OverlayDescriptor>,
+
     pub Context: MaybeThreadContext,
+    // Why the context was not decoded, if it does not match the dump
+    pub ContextError: Option<String>,
+    pub Info: Option<ThreadInfo>,
+    pub Name: Option<String>,
+
+    // Emulated x86 context of a thread in a WOW64 process
+    pub Wow64Context: Option<ContextX86>,
 }
 
 #[derive(Serialize)]
@@ -143,6 +939,10 @@ pub struct ExceptionStream {
     pub ThreadContext: LocationDescriptor,
 
     pub Context: MaybeThreadContext,
+    pub ContextError: Option<String>,
+
+    // Emulated x86 context of the thread in a WOW64 process
+    pub Wow64Context: Option<ContextX86>,
 }
 
 #[derive(Serialize)]
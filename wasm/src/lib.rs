@@ -1,3 +1,7 @@
+// Exports are only called by the JS glue code, which owns the buffers passed
+// in and guarantees they came from `buffer_alloc`.
+#![allow(clippy::missing_safety_doc)]
+
 extern crate revisa_minidump;
 
 use revisa_minidump as minidump;
@@ -5,6 +9,14 @@ use revisa_minidump as minidump;
 // Use opaque buffer type for interop
 type WasmBuffer = Vec<u8>;
 
+// Box up JSON result for JS, reporting any failure as a JSON error object
+// rather than trapping the WASM instance.
+fn json_result(result: Result<Vec<u8>, minidump::ParseError>) -> *mut WasmBuffer {
+    let json = result.unwrap_or_else(|e| minidump::error_json(&e));
+    let boxed = Box::new(json);
+    Box::into_raw(boxed)
+}
+
 #[no_mangle]
 pub unsafe fn buffer_alloc(size: usize) -> *mut WasmBuffer {
     let vec = vec![0u8; size];
@@ -27,42 +39,92 @@ pub unsafe fn buffer_free(raw: *mut WasmBuffer) {
     drop(Box::from_raw(raw));
 }
 
+// Summarize minidump header and annotations
+#[no_mangle]
+pub unsafe fn minidump_summary(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::summary_json(&*raw))
+}
+
 // Find available minidump overlay data
 #[no_mangle]
 pub unsafe fn minidump_memory_overlay(raw: *mut WasmBuffer) -> *mut WasmBuffer {
-    let json = minidump::memory_overlay_json(&*raw);
-    let boxed = Box::new(json);
-    Box::into_raw(boxed)
+    json_result(minidump::memory_overlay_json(&*raw))
 }
 
 // Analyze memory data in a minidump
 #[no_mangle]
 pub unsafe fn minidump_memory_analysis(raw: *mut WasmBuffer) -> *mut WasmBuffer {
-    let json = minidump::memory_analysis_json(&*raw);
-    let boxed = Box::new(json);
-    Box::into_raw(boxed)
+    json_result(minidump::memory_analysis_json(&*raw))
 }
 
 // Find thread list in a minidump
 #[no_mangle]
 pub unsafe fn minidump_thread_list(raw: *mut WasmBuffer) -> *mut WasmBuffer {
-    let json = minidump::thread_list_json(&*raw);
-    let boxed = Box::new(json);
-    Box::into_raw(boxed)
+    json_result(minidump::thread_list_json(&*raw))
+}
+
+// Find module list in a minidump
+#[no_mangle]
+pub unsafe fn minidump_module_list(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::module_list_json(&*raw))
+}
+
+// Find unloaded module list in a minidump
+#[no_mangle]
+pub unsafe fn minidump_unloaded_module_list(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::unloaded_module_list_json(&*raw))
+}
+
+// Find Breakpad and Crashpad crash details in a minidump
+#[no_mangle]
+pub unsafe fn minidump_crash_info(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::crash_info_json(&*raw))
+}
+
+// Find Linux process information in a minidump
+#[no_mangle]
+pub unsafe fn minidump_linux_info(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::linux_info_json(&*raw))
+}
+
+// Find dynamic function tables in a minidump
+#[no_mangle]
+pub unsafe fn minidump_function_table_list(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::function_table_list_json(&*raw))
+}
+
+// Find handle table in a minidump
+#[no_mangle]
+pub unsafe fn minidump_handle_list(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::handle_list_json(&*raw))
+}
+
+// Find handle operations recorded by Application Verifier in a minidump
+#[no_mangle]
+pub unsafe fn minidump_handle_operation_list(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::handle_operation_list_json(&*raw))
+}
+
+// Find security tokens in a minidump
+#[no_mangle]
+pub unsafe fn minidump_token_list(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::token_list_json(&*raw))
 }
 
 // Find exception record in a minidump
 #[no_mangle]
 pub unsafe fn minidump_exception_record(raw: *mut WasmBuffer) -> *mut WasmBuffer {
-    let json = minidump::exception_record_json(&*raw);
-    let boxed = Box::new(json);
-    Box::into_raw(boxed)
+    json_result(minidump::exception_record_json(&*raw))
+}
+
+// Find misc info record in a minidump
+#[no_mangle]
+pub unsafe fn minidump_misc_info(raw: *mut WasmBuffer) -> *mut WasmBuffer {
+    json_result(minidump::misc_info_json(&*raw))
 }
 
 // Find system info record in a minidump
 #[no_mangle]
 pub unsafe fn minidump_system_info(raw: *mut WasmBuffer) -> *mut WasmBuffer {
-    let json = minidump::system_info_json(&*raw);
-    let boxed = Box::new(json);
-    Box::into_raw(boxed)
+    json_result(minidump::system_info_json(&*raw))
 }
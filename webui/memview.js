@@ -22,6 +22,8 @@ class MemoryData {
 
             // Sorted from highest start address to lowest
             this.ranges = JSON.parse(result.memory_range);
+            if (this.ranges.Error)
+                this.ranges = [];
             this.ranges.sort((l,r) => (l.Address - r.Address));
             this.ranges.reverse();
 
@@ -90,6 +92,9 @@ class MemoryViewer {
         // Set address near fault address on minidump load
         this.control.subscribe("minidump", (raw, result) => {
             let exception_record = JSON.parse(result.exception_record);
+            if (exception_record.Error)
+                return;
+
             let fault_addr = exception_record.Exception.Address;
             fault_addr = fault_addr - (fault_addr & 0xF);
             this.model.set_address(fault_addr, this.nrow / 2);
@@ -53,6 +53,11 @@ class MinidumpProcessor {
         return json;
     }
 
+    wasm_summary(wasm_buf) {
+        let res = wasm.exports.minidump_summary(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
     wasm_memory_overlay(wasm_buf) {
         let res = wasm.exports.minidump_memory_overlay(wasm_buf);
         return this.wasm_to_json(res);
@@ -68,6 +73,46 @@ class MinidumpProcessor {
         return this.wasm_to_json(res);
     }
 
+    wasm_module_list(wasm_buf) {
+        let res = wasm.exports.minidump_module_list(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
+    wasm_unloaded_module_list(wasm_buf) {
+        let res = wasm.exports.minidump_unloaded_module_list(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
+    wasm_crash_info(wasm_buf) {
+        let res = wasm.exports.minidump_crash_info(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
+    wasm_linux_info(wasm_buf) {
+        let res = wasm.exports.minidump_linux_info(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
+    wasm_function_table_list(wasm_buf) {
+        let res = wasm.exports.minidump_function_table_list(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
+    wasm_handle_list(wasm_buf) {
+        let res = wasm.exports.minidump_handle_list(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
+    wasm_handle_operation_list(wasm_buf) {
+        let res = wasm.exports.minidump_handle_operation_list(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
+    wasm_token_list(wasm_buf) {
+        let res = wasm.exports.minidump_token_list(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
     wasm_exception_record(wasm_buf) {
         let res = wasm.exports.minidump_exception_record(wasm_buf);
         return this.wasm_to_json(res);
@@ -78,6 +123,11 @@ class MinidumpProcessor {
         return this.wasm_to_json(res);
     }
 
+    wasm_misc_info(wasm_buf) {
+        let res = wasm.exports.minidump_misc_info(wasm_buf);
+        return this.wasm_to_json(res);
+    }
+
     process(data) {
         // Copy minidump to WASM memory
         let wasm_buf = this.data_to_wasm(data);
@@ -87,11 +137,21 @@ class MinidumpProcessor {
             'topic': 'result',
             'magic': this.get_magic(data),
             'bytelen': data.byteLength,
+            'summary': this.wasm_summary(wasm_buf),
             'memory_info': this.wasm_memory_analysis(wasm_buf),
             'memory_range': this.wasm_memory_overlay(wasm_buf),
             'thread_list': this.wasm_thread_list(wasm_buf),
+            'module_list': this.wasm_module_list(wasm_buf),
+            'unloaded_module_list': this.wasm_unloaded_module_list(wasm_buf),
+            'crash_info': this.wasm_crash_info(wasm_buf),
+            'linux_info': this.wasm_linux_info(wasm_buf),
+            'function_table_list': this.wasm_function_table_list(wasm_buf),
+            'handle_list': this.wasm_handle_list(wasm_buf),
+            'handle_operation_list': this.wasm_handle_operation_list(wasm_buf),
+            'token_list': this.wasm_token_list(wasm_buf),
             'exception_record': this.wasm_exception_record(wasm_buf),
             'system_info': this.wasm_system_info(wasm_buf),
+            'misc_info': this.wasm_misc_info(wasm_buf),
         };
 
         // Release WASM memory
@@ -187,6 +187,36 @@ class MinidumpViewer {
         }
     }
 
+    render_memory_analysis(analysis, dom) {
+        if (analysis.Diagnostics.length > 0) {
+            let list = document.createElement('ul');
+            list.className = "diagnostics";
+            for (let msg of analysis.Diagnostics) {
+                let li = document.createElement('li');
+                li.append(msg);
+                list.append(li);
+            }
+            dom.append(list);
+        }
+
+        this.render_memory(analysis.Regions, dom);
+    }
+
+    // Render a JSON result, or the error which was reported in its place
+    render_result(json, dom, render_fn) {
+        let item = JSON.parse(json);
+
+        if (item.Error) {
+            let elem = document.createElement('span');
+            elem.className = "error";
+            elem.append("Error: " + item.Error);
+            dom.append(elem);
+            return;
+        }
+
+        render_fn.call(this, item, dom);
+    }
+
     render_memory_range(mem_range, dom) {
         let list = document.createElement('ul');
 
@@ -213,18 +243,276 @@ class MinidumpViewer {
         let threads = Array.from(thread_list);
 
         for (let item of threads) {
-            let pc = item.Context.type == "X86" ? item.Context.Eip
-                                                : item.Context.Rip;
-
             let elem = document.createElement('li');
             elem.append("Thread[" + item.ThreadId.toString().padStart(5, '\u00A0') + "]");
-            elem.append(" IP[" + pc.toString(16).padStart(12, '0') + "]");
+            if (item.Name)
+                elem.append(" \"" + item.Name + "\"");
+            if (item.InstructionPointer != null)
+                elem.append(" IP[" + item.InstructionPointer.toString(16).padStart(12, '0') + "]");
+            if (item.IpLocation)
+                elem.append(" " + item.IpLocation);
+            if (item.Wow64Context && item.Wow64Context.Eip != null)
+                elem.append(" WOW64[" + item.Wow64Context.Eip.toString(16).padStart(8, '0') + "]");
+
+            // Thread times are in 100ns units
+            if (item.Info) {
+                let cpu = (item.Info.KernelTime + item.Info.UserTime) / 1e7;
+                elem.append(" CPU[" + cpu.toFixed(3) + "s]");
+                elem.append(" Start[" + item.Info.StartAddress.toString(16).padStart(12, '0') + "]");
+            }
+
+            list.appendChild(elem);
+        }
+        dom.appendChild(list);
+    }
+
+    render_module_list(module_list, dom) {
+        let list = document.createElement('ul');
+
+        for (let item of module_list) {
+            let elem = document.createElement('li');
+            elem.append(item.BaseOfImage.toString(16).padStart(12, '0'));
+            elem.append(" " + MemoryFlags.FormatSize(item.SizeOfImage).padStart(6, '\u00A0'));
+            elem.append(" " + (item.ModuleName || "<unknown>"));
+
+            if (item.VersionInfo)
+                elem.append(" [" + item.VersionInfo.FileVersion + "]");
+            if (item.DebugIdentifier)
+                elem.append(" " + item.DebugIdentifier);
 
             list.appendChild(elem);
         }
         dom.appendChild(list);
     }
 
+    render_unloaded_module_list(module_list, dom) {
+        let list = document.createElement('ul');
+
+        for (let item of module_list) {
+            let elem = document.createElement('li');
+            elem.append(item.BaseOfImage.toString(16).padStart(12, '0'));
+            elem.append(" " + MemoryFlags.FormatSize(item.SizeOfImage).padStart(6, '\u00A0'));
+            elem.append(" " + (item.ModuleName || "<unknown>"));
+
+            list.appendChild(elem);
+        }
+        dom.appendChild(list);
+    }
+
+    render_crash_info(item, dom) {
+        let list = document.createElement('ul');
+
+        if (item.BreakpadInfo) {
+            let info = item.BreakpadInfo;
+            let li = document.createElement('li');
+            let tid = val => (val != null ? val.toString() : "<unknown>");
+            li.append("Breakpad Dump Thread: " + tid(info.DumpThreadId));
+            li.append(" Requesting Thread: " + tid(info.RequestingThreadId));
+            list.append(li);
+        }
+
+        if (item.AssertionInfo) {
+            let info = item.AssertionInfo;
+            let kinds = ["Unknown", "Invalid Parameter", "Pure Virtual Call"];
+            let li = document.createElement('li');
+            li.append("Assertion: " + (kinds[info.Type] || info.Type) + " " + info.Expression);
+            li.append(" in " + info.Function + " (" + info.File + ":" + info.Line + ")");
+            list.append(li);
+        }
+
+        if (item.CrashpadInfo) {
+            let info = item.CrashpadInfo;
+            let li = document.createElement('li');
+            li.append("Crashpad Report: " + info.ReportId + " Client: " + info.ClientId);
+
+            let annotations = document.createElement('ul');
+            for (let [key, val] of Object.entries(info.SimpleAnnotations)) {
+                let elem = document.createElement('li');
+                elem.append(key + " = " + val);
+                annotations.append(elem);
+            }
+            for (let module of info.ModuleList) {
+                let name = module.ModuleName || "<unknown>";
+                for (let [key, val] of Object.entries(module.SimpleAnnotations)) {
+                    let elem = document.createElement('li');
+                    elem.append(name + ": " + key + " = " + val);
+                    annotations.append(elem);
+                }
+                for (let val of module.ListAnnotations) {
+                    let elem = document.createElement('li');
+                    elem.append(name + ": " + val);
+                    annotations.append(elem);
+                }
+                for (let obj of module.AnnotationObjects) {
+                    let elem = document.createElement('li');
+                    let val = (obj.Value != null ? obj.Value : obj.Data);
+                    elem.append(name + ": " + obj.Name + " = " + val);
+                    annotations.append(elem);
+                }
+            }
+            li.append(annotations);
+            list.append(li);
+        }
+
+        dom.append(list);
+    }
+
+    render_linux_info(item, dom) {
+        let list = document.createElement('ul');
+
+        let release = item.LsbRelease;
+        let props = [
+            ["Distribution", release && (release.PRETTY_NAME || release.DISTRIB_DESCRIPTION)],
+            ["Command Line", item.CmdLine && item.CmdLine.join(" ")],
+            ["CPU", item.CpuInfo && item.CpuInfo.length && item.CpuInfo[0]["model name"]],
+            ["Process Id", item.ProcStatus && item.ProcStatus.Pid],
+            ["Peak Virtual Memory", item.ProcStatus && item.ProcStatus.VmPeak],
+        ];
+
+        for (let [disp, val] of props) {
+            if (!val)
+                continue;
+
+            let li = document.createElement('li');
+            li.append(disp, ": ", val.toString());
+            list.append(li);
+        }
+        dom.append("Linux Process:", list);
+
+        if (item.Maps) {
+            let maps = document.createElement('ul');
+            for (let map of item.Maps) {
+                let li = document.createElement('li');
+                li.append(map.Start.toString(16).padStart(12, '0'),
+                          " - ",
+                          map.End.toString(16).padStart(12, '0'),
+                          " " + map.Permissions);
+                if (map.Path)
+                    li.append(" " + map.Path);
+                maps.append(li);
+            }
+            dom.append("Mappings:", maps);
+        }
+    }
+
+    render_function_table_list(table_list, dom) {
+        let list = document.createElement('ul');
+
+        for (let item of table_list) {
+            let elem = document.createElement('li');
+            elem.append(item.MinimumAddress.toString(16).padStart(12, '0'),
+                        " - ",
+                        item.MaximumAddress.toString(16).padStart(12, '0'));
+            elem.append(" Functions[" + item.EntryCount + "]");
+
+            list.appendChild(elem);
+        }
+        dom.appendChild(list);
+    }
+
+    render_handle_list(handle_list, dom) {
+        let list = document.createElement('ul');
+
+        for (let item of handle_list) {
+            let elem = document.createElement('li');
+            elem.append(item.Handle.toString(16).padStart(8, '0'));
+            elem.append(" " + (item.TypeName || "<unknown>").padEnd(16, '\u00A0'));
+            if (item.ObjectName)
+                elem.append(" " + item.ObjectName);
+
+            for (let info of item.ObjectInfo) {
+                let details = info.Details;
+                if (!details)
+                    continue;
+
+                if (details.type == "Mutant") {
+                    elem.append(" Count[" + details.CurrentCount + "]");
+                    if (details.AbandonedState)
+                        elem.append(" Abandoned");
+                }
+                else if (details.type == "MutantOwner") {
+                    elem.append(" Owner[" + details.ThreadId + "]");
+                }
+                else if (details.type == "Thread") {
+                    elem.append(" Thread[" + details.ThreadId + "]");
+                }
+                else if (details.type == "Process") {
+                    elem.append(" Process[" + details.ProcessId + "]");
+                }
+            }
+
+            list.appendChild(elem);
+        }
+        dom.appendChild(list);
+    }
+
+    render_handle_operation_list(history_list, dom) {
+        let op_names = ["Unused", "Open", "Close", "BadRef"];
+
+        let list = document.createElement('ul');
+
+        for (let history of history_list) {
+            let elem = document.createElement('li');
+            elem.append(history.Handle.toString(16).padStart(8, '0'));
+
+            // Flag handles which were closed again without being reopened
+            let open = true;
+            let ops = document.createElement('ul');
+            for (let item of history.Operations) {
+                let li = document.createElement('li');
+                li.append((op_names[item.OperationType] || item.OperationType.toString()) +
+                          " Thread[" + item.ThreadId + "]");
+                if (item.BackTraceLocations.length > 0)
+                    li.append(" " + item.BackTraceLocations.map(l => l || "?").join(" < "));
+
+                if (item.OperationType == 2) {
+                    if (!open)
+                        li.className = "error";
+                    open = false;
+                }
+                else if (item.OperationType == 1) {
+                    open = true;
+                }
+
+                ops.append(li);
+            }
+
+            elem.append(ops);
+            list.appendChild(elem);
+        }
+        dom.appendChild(list);
+    }
+
+    render_token_list(token_list, dom) {
+        for (let item of token_list) {
+            let list = document.createElement('ul');
+
+            let integrity = item.IntegrityLevelName;
+            if (integrity == null && item.IntegrityLevel != null)
+                integrity = "0x" + item.IntegrityLevel.toString(16);
+
+            let privileges = item.Privileges.filter(p => p.Enabled).map(p => p.Name);
+
+            let props = [
+                ["User", item.User],
+                ["Integrity Level", integrity],
+                ["Groups", item.Groups.length ? item.Groups.join(", ") : null],
+                ["Enabled Privileges", privileges.length ? privileges.join(", ") : null],
+            ];
+
+            for (let [disp, val] of props) {
+                if (val == null)
+                    continue;
+
+                let li = document.createElement('li');
+                li.append(disp, ": ", val.toString());
+                list.append(li);
+            }
+
+            dom.append("Token[" + item.TokenId + "]:", list);
+        }
+    }
+
     render_exception_record(item, dom) {
         let addr = item.Exception.Address;
 
@@ -234,11 +522,34 @@ class MinidumpViewer {
 
         li_thread.append("Thread[" + item.ThreadId.toString() + "]");
         li_fault.append("FaultAddress[" + addr.toString(16).padStart(12, '0') + "]");
+        if (item.FaultLocation)
+            li_fault.append(" " + item.FaultLocation);
 
         list.append(li_thread, li_fault);
         dom.append("Exception Record:", list);
     }
 
+    render_summary(item, dom) {
+        let list = document.createElement('ul');
+
+        let props = [
+            ["Timestamp", new Date(item.TimeDateStamp * 1000).toISOString()],
+            ["Comment", item.CommentA],
+            ["Comment", item.CommentW],
+        ];
+
+        for (let [disp, val] of props) {
+            if (val == null)
+                continue;
+
+            let li = document.createElement('li');
+            li.append(disp, ": ", val.toString());
+            list.append(li);
+        }
+
+        dom.append("Summary:", list);
+    }
+
     render_system_info(item, dom) {
         let list = document.createElement('ul');
 
@@ -257,7 +568,34 @@ class MinidumpViewer {
             ["Processor Count", item.NumberOfProcessors],
         ];
 
+        // Memory usage, to tell process and system exhaustion apart
+        let sysmem = item.SystemMemoryInfo;
+        if (sysmem) {
+            let page = sysmem.BasicInfo.PageSize;
+            let perf = sysmem.BasicPerfInfo;
+            props.push(
+                ["System Commit", MemoryFlags.FormatSize(perf.CommittedPages * page) + " / " +
+                                  MemoryFlags.FormatSize(perf.CommitLimit * page)],
+                ["System Available", MemoryFlags.FormatSize(perf.AvailablePages * page)],
+            );
+        }
+
+        let vm = item.ProcessVmCounters;
+        if (vm) {
+            let size = sz => sz == null ? null : MemoryFlags.FormatSize(sz);
+            props.push(
+                ["Process Private", size(vm.PrivateUsage)],
+                ["Process Working Set", size(vm.WorkingSetSize)],
+                ["Process Peak Working Set", size(vm.PeakWorkingSetSize)],
+                ["Process Pagefile", size(vm.PagefileUsage)],
+                ["Process Virtual Size", size(vm.VirtualSize)],
+            );
+        }
+
         for (let [disp, val] of props) {
+            if (val == null)
+                continue;
+
             let li = document.createElement('li');
             li.append(disp, ": ", val.toString());
             list.append(li);
@@ -266,6 +604,28 @@ class MinidumpViewer {
         dom.append("System Information:", list);
     }
 
+    render_misc_info(item, dom) {
+        let list = document.createElement('ul');
+
+        let props = [
+            ["Process Id", item.ProcessId],
+            ["Process Uptime", item.ProcessUptime != null ? item.ProcessUptime + "s" : null],
+            ["Processor MHz", item.ProcessorCurrentMhz],
+            ["Build String", item.BuildString],
+        ];
+
+        for (let [disp, val] of props) {
+            if (val == null)
+                continue;
+
+            let li = document.createElement('li');
+            li.append(disp, ": ", val.toString());
+            list.append(li);
+        }
+
+        dom.append("Process Information:", list);
+    }
+
     show_result(result) {
         this.body.innerHTML = "";
 
@@ -279,39 +639,116 @@ class MinidumpViewer {
         li_sig.append("Header Signature: " + result.magic);
         li_size.append("Data Size: " + result.bytelen);
 
+        let summary_dom = document.createElement('li');
+        this.render_result(result.summary, summary_dom,
+                           this.render_summary);
+
         let exception_dom = document.createElement('li');
-        let exception_record = JSON.parse(result.exception_record);
-        this.render_exception_record(exception_record, exception_dom);
+        this.render_result(result.exception_record, exception_dom,
+                           this.render_exception_record);
 
         let sysinfo_dom = document.createElement('li');
-        let system_info = JSON.parse(result.system_info);
-        this.render_system_info(system_info, sysinfo_dom);
+        this.render_result(result.system_info, sysinfo_dom,
+                           this.render_system_info);
+
+        let miscinfo_dom = document.createElement('li');
+        this.render_result(result.misc_info, miscinfo_dom,
+                           this.render_misc_info);
+
+        // Crash details are only present in dumps written by Breakpad or Crashpad
+        let crash_dom = document.createElement('li');
+        let crash = JSON.parse(result.crash_info);
+        if (crash.Error || Object.values(crash).some(val => val != null)) {
+            this.render_result(result.crash_info, crash_dom,
+                               this.render_crash_info);
+        }
 
-        list.append(li_sig, li_size, sysinfo_dom, exception_dom);
+        list.append(li_sig, li_size, summary_dom, sysinfo_dom, miscinfo_dom, exception_dom);
+        if (crash_dom.hasChildNodes())
+            list.append(crash_dom);
         this.body.append(list);
 
+        // Linux streams are only present in dumps written by Breakpad
+        let linux = JSON.parse(result.linux_info);
+        if (linux.Error || Object.values(linux).some(val => val != null)) {
+            head = document.createElement('h1');
+            head.textContent = "Linux";
+            let linux_dom = document.createElement('div');
+            linux_dom.className = "modules";
+            this.render_result(result.linux_info, linux_dom,
+                               this.render_linux_info);
+            this.body.append(head, linux_dom);
+        }
+
         head = document.createElement('h1');
         head.textContent = "Thread List";
         let threads_dom = document.createElement('div');
         threads_dom.className = "threads";
-        let thread_list = JSON.parse(result.thread_list);
-        this.render_thread_list(thread_list, threads_dom);
+        this.render_result(result.thread_list, threads_dom,
+                           this.render_thread_list);
         this.body.append(head, threads_dom);
 
+        head = document.createElement('h1');
+        head.textContent = "Module List";
+        let modules_dom = document.createElement('div');
+        modules_dom.className = "modules";
+        this.render_result(result.module_list, modules_dom,
+                           this.render_module_list);
+        this.body.append(head, modules_dom);
+
+        head = document.createElement('h1');
+        head.textContent = "Unloaded Module List";
+        let unloaded_dom = document.createElement('div');
+        unloaded_dom.className = "modules";
+        this.render_result(result.unloaded_module_list, unloaded_dom,
+                           this.render_unloaded_module_list);
+        this.body.append(head, unloaded_dom);
+
+        head = document.createElement('h1');
+        head.textContent = "Dynamic Function Tables";
+        let functables_dom = document.createElement('div');
+        functables_dom.className = "modules";
+        this.render_result(result.function_table_list, functables_dom,
+                           this.render_function_table_list);
+        this.body.append(head, functables_dom);
+
+        head = document.createElement('h1');
+        head.textContent = "Handle Operations";
+        let handleops_dom = document.createElement('div');
+        handleops_dom.className = "handles";
+        this.render_result(result.handle_operation_list, handleops_dom,
+                           this.render_handle_operation_list);
+        this.body.append(head, handleops_dom);
+
+        head = document.createElement('h1');
+        head.textContent = "Security Tokens";
+        let tokens_dom = document.createElement('div');
+        this.render_result(result.token_list, tokens_dom,
+                           this.render_token_list);
+        this.body.append(head, tokens_dom);
+
+        head = document.createElement('h1');
+        head.textContent = "Handle List";
+        let handles_dom = document.createElement('div');
+        handles_dom.className = "handles";
+        this.render_result(result.handle_list, handles_dom,
+                           this.render_handle_list);
+        this.body.append(head, handles_dom);
+
         head = document.createElement('h1');
         head.textContent = "Memory Dump Ranges";
         let memdata_dom = document.createElement('div');
         memdata_dom.className = "memdata";
-        let mem_range = JSON.parse(result.memory_range);
-        this.render_memory_range(mem_range, memdata_dom);
+        this.render_result(result.memory_range, memdata_dom,
+                           this.render_memory_range);
         this.body.append(head, memdata_dom);
 
         head = document.createElement('h1');
         head.textContent = "Memory Map";
         let mem_dom = document.createElement('div');
         mem_dom.className = "meminfo";
-        let mem_info = JSON.parse(result.memory_info);
-        this.render_memory(mem_info, mem_dom);
+        this.render_result(result.memory_info, mem_dom,
+                           this.render_memory_analysis);
         this.body.append(head, mem_dom);
     }
 